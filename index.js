@@ -1,215 +1,78 @@
-const { is_validSolution } = require("./native/index.node");
-
-function writeCompactSize(length, buffer, pos) {
-  let bytesWritten;
-  if (length < 253) {
-    buffer.writeUInt8(length, pos);
-    bytesWritten = 1;
-  } else if (length <= 0xffff) {
-    buffer.writeUInt8(253, pos);
-    buffer.writeUInt16LE(length, pos + 1);
-    bytesWritten = 3;
-  } else if (length <= 0xffffffff) {
-    buffer.writeUInt8(254, pos);
-    buffer.writeUInt32LE(length, pos + 1);
-    bytesWritten = 5;
-  } else {
-    buffer.writeUInt8(255, pos);
-    buffer.writeBigUInt64LE(BigInt(length), pos + 1);
-    bytesWritten = 9;
+const {
+  is_validSolution,
+  is_valid_solution,
+  verifySolutionDetailed,
+  verifySolutionAsync,
+  headerContextNew,
+  headerContextVerify,
+  headerContextVerifyDetailed,
+  setMaxInFlight,
+  verifyBatch,
+  solve,
+  indicesToMinimal,
+  indicesFromMinimal,
+  parseHeader,
+  parseBlockHeader,
+  encodeHeader,
+  getDataForEquihashValidation,
+  verifyRawHeader,
+  checkProofOfWork,
+} = require("./native/index.node");
+
+// Absorbs a header once so that many (nonce, solution) pairs can be checked
+// against it, e.g. every share submitted for one pool job.
+class HeaderContext {
+  constructor(n, k, header, personalization) {
+    this.boxed = headerContextNew(n, k, header, personalization);
   }
-  return bytesWritten;
-}
-
-function readCompactSizeFromHex(input_hex, pos) {
-  // Parse the prefix to determine how many bytes are used for the value
-  const prefixHex = input_hex.substring(pos, pos + 2);
-  const prefix = parseInt(prefixHex, 16);
-  let value = 0;
-  let bytesRead = 0;
 
-  if (prefix < 0xfd) {
-    // The prefix is the value
-    value = prefix;
-    bytesRead = 1;
-  } else if (prefix === 0xfd) {
-    // The next two bytes are the value
-    const sizeHexLE = input_hex.substring(pos + 2, pos + 6); // Little-endian hex string
-    const sizeHex = sizeHexLE.substring(2, 4) + sizeHexLE.substring(0, 2); // Convert to big-endian
-    value = parseInt(sizeHex, 16);
-    bytesRead = 3;
-  } else if (prefix === 0xfe) {
-    // The next four bytes are the value
-    const sizeHexLE = input_hex.substring(pos + 2, pos + 10); // Little-endian hex string
-    const sizeHex =
-      sizeHexLE.substring(6, 8) +
-      sizeHexLE.substring(4, 6) +
-      sizeHexLE.substring(2, 4) +
-      sizeHexLE.substring(0, 2); // Convert to big-endian
-    value = parseInt(sizeHex, 16);
-    bytesRead = 5;
-  } else if (prefix === 0xff) {
-    // The next eight bytes are the value
-    const sizeHexLE = input_hex.substring(pos + 2, pos + 18); // Little-endian hex string
-    const sizeHex =
-      sizeHexLE.substring(14, 16) +
-      sizeHexLE.substring(12, 14) +
-      sizeHexLE.substring(10, 12) +
-      sizeHexLE.substring(8, 10) +
-      sizeHexLE.substring(6, 8) +
-      sizeHexLE.substring(4, 6) +
-      sizeHexLE.substring(2, 4) +
-      sizeHexLE.substring(0, 2); // Convert to big-endian
-    value = parseInt(sizeHex, 16);
-    bytesRead = 9;
-  } else {
-    // Unsupported format
-    return "Unsupported format";
+  verify(nonce, solution) {
+    return headerContextVerify(this.boxed, nonce, solution);
   }
 
-  let hexLength = bytesRead * 2;
-  return { value, hexLength };
+  verifyDetailed(nonce, solution) {
+    return headerContextVerifyDetailed(this.boxed, nonce, solution);
+  }
 }
 
-function readCompactSize(inputBuffer, pos) {
-  // Parse the prefix to determine how many bytes are used for the value
-  const prefix = inputBuffer.readUInt8(pos);
-  let value = 0;
-  let bytesRead = 0;
-
-  if (prefix < 0xfd) {
-    // The prefix is the value
-    value = prefix;
-    bytesRead = 1;
-  } else if (prefix === 0xfd) {
-    // The next two bytes are the value, read as little-endian
-    value = inputBuffer.readUInt16LE(pos + 1);
-    bytesRead = 3; // Prefix + 2 bytes
-  } else if (prefix === 0xfe) {
-    // The next four bytes are the value, read as little-endian
-    value = inputBuffer.readUInt32LE(pos + 1);
-    bytesRead = 5; // Prefix + 4 bytes
-  } else if (prefix === 0xff) {
-    // The next eight bytes are the value, read as little-endian
-    // Note: JavaScript numbers can only safely represent integers up to 2^53 - 1,
-    // but we are reading this as a buffer, not a number, due to JavaScript limitations.
-    value = inputBuffer.readBigUInt64LE(pos + 1);
-    bytesRead = 9; // Prefix + 8 bytes
+// Length in bytes of the compact size prefix for `length`.
+function getCompactSizeLength(length) {
+  if (length < 253) {
+    return 1;
+  } else if (length <= 0xffff) {
+    return 3;
+  } else if (length <= 0xffffffff) {
+    return 5;
   } else {
-    // Unsupported format
-    return "Unsupported format";
+    return 9;
   }
-
-  return { value, bytesRead };
 }
 
+// Deprecated: use parseBlockHeader. Kept with its original return shape for
+// existing callers.
 function parseBlockData(rawBlockHexString) {
-  // calculate size of the v4 data without nonce and solution
-  const v4HexDataWithoutNonceAndSolutionSize = 2 * (4 + 32 + 32 + 32 + 4 + 4);
-  const uint256Size = 2 * 32;
-
-  let pos = 0;
-  const v4HexDataWithoutNonceAndSolution = rawBlockHexString.substring(
-    pos,
-    pos + v4HexDataWithoutNonceAndSolutionSize
-  );
-  console.log(
-    `V4 Data Without Nonce and Solution: ${v4HexDataWithoutNonceAndSolution}`
-  );
-  pos += v4HexDataWithoutNonceAndSolutionSize;
-
-  const nonceValue = rawBlockHexString.substring(pos, pos + uint256Size);
-  console.log(`Nonce Value (hex): ${nonceValue}`);
-  pos += uint256Size;
+  const header = parseBlockHeader(rawBlockHexString);
+  const headerHex = rawBlockHexString.substring(0, 2 * header.size);
 
-  // solution compact size and value
-  const solutionSize = readCompactSizeFromHex(rawBlockHexString, pos);
-  console.log(`Solution Size Hex Length: ${solutionSize.hexLength}`);
-  console.log(`Solution Size: ${solutionSize.value}`);
-  pos += solutionSize.hexLength; // Move past solution compact size in hex string
-  const solutionValue = rawBlockHexString.substring(
-    pos,
-    pos + solutionSize.value * 2
-  );
-  console.log(`Solution (hex): ${solutionValue}`);
-  pos += solutionSize.value * 2;
-
-  const v5_data_combined_with_tx_data = rawBlockHexString.substring(pos);
-
-  // Extracting PastelID
-  let pos_v5 = 0;
-  const pastelIDSize = readCompactSizeFromHex(v5_data_combined_with_tx_data, pos_v5);
-  console.log(`Pastel ID Size Hex Length: ${pastelIDSize.hexLength}`);
-  console.log(`Pastel ID Size: ${pastelIDSize.value}`);
-  pos_v5 += pastelIDSize.hexLength; // Move past Pastel ID compact size in hex string
-
-  const pastelid_in_hex = v5_data_combined_with_tx_data.substring(
-    pos_v5,
-    pos_v5 + pastelIDSize.value * 2);
-  console.log(`Pastel ID (hex): ${pastelid_in_hex}`);
-  const pastelIDString = Buffer.from(pastelid_in_hex, "hex").toString("utf8");
-  console.log(`Pastel ID: ${pastelIDString}`);
-  pos_v5 += pastelIDSize.value * 2;
-
-  // Extracting Signature
-  const signatureSize = readCompactSizeFromHex(v5_data_combined_with_tx_data, pos_v5);
-  console.log(`Signature Size Hex Length: ${signatureSize.hexLength}`);
-  console.log(`Signature Size: ${signatureSize.value}`);
-  pos_v5 += signatureSize.hexLength; // Move past Signature compact size in hex string
-
-  const signatureValue = v5_data_combined_with_tx_data.substring(
-    pos_v5,
-    pos_v5 + signatureSize.value * 2
-  );
-  console.log(`Signature (hex): ${signatureValue}`);
-  pos_v5 += signatureSize.value * 2;
-
-  const blockTxData = v5_data_combined_with_tx_data.substring(pos_v5);
-  console.log(`Block TX Data: ${blockTxData}`);
-
-  const v5_data_combined = v5_data_combined_with_tx_data.substring(
-    0,
-    v5_data_combined_with_tx_data.length - blockTxData.length
-  );
-  console.log(`V5 Data Combined (hex): ${v5_data_combined}`);
-
-  return {
-    v4_data_without_nonce_and_solution: v4HexDataWithoutNonceAndSolution,
-    nonce_value_in_hex: nonceValue,
-    solution_value_in_hex: solutionValue,
-    pastelid_value_in_hex: pastelid_in_hex,
-    signature_value_in_hex: signatureValue,
-    v5_data_combined_in_hex: v5_data_combined,
-  };
-}
-
-function getDataForEquihashValidation(rawBlockHexString) {
-  const parsedData = parseBlockData(rawBlockHexString);
-  const equihashInputHex = `${parsedData.v4_data_without_nonce_and_solution}${parsedData.v5_data_combined_in_hex}${parsedData.nonce_value_in_hex}`;
-  const solutionHex = parsedData.solution_value_in_hex;
-
-  console.log("Equihash Input (hex):", equihashInputHex);
-  console.log("Solution (hex):", solutionHex);
+  // Everything up to bits, then the nonce, then the solution with its length.
+  const v4Size = 2 * (4 + 32 + 32 + 32 + 4 + 4);
+  const solutionSize = header.solution.length / 2;
+  const v5Start =
+    v4Size + 2 * 32 + 2 * getCompactSizeLength(solutionSize) + 2 * solutionSize;
 
   return {
-    equihash_input_hex_string: equihashInputHex,
-    solution_hex_string: solutionHex,
+    v4_data_without_nonce_and_solution: headerHex.substring(0, v4Size),
+    nonce_value_in_hex: header.nonce,
+    solution_value_in_hex: header.solution,
+    pastelid_value_in_hex: Buffer.from(header.pastelId, "utf8").toString("hex"),
+    signature_value_in_hex: header.signature,
+    v5_data_combined_in_hex: headerHex.substring(v5Start),
   };
 }
 
-// function to get compact size length
-function getCompactSizeLength(length) {
-  if (length < 253) {
-      return 1;
-  } else if (length <= 0xffff) {
-      return 3;
-  } else if (length <= 0xffffffff) {
-      return 5;
-  } else {
-      return 9;
-  }
-}
-
+// Deprecated: use encodeHeader. Kept with its original positional arguments
+// and Buffer result for existing callers. The PastelID is given as the hex of
+// its UTF-8 bytes and `difficulty_bits` as the serialized (little-endian) hex.
 function serializeHeader(
   nTime,
   nonce,
@@ -222,191 +85,39 @@ function serializeHeader(
   signature_in_hex,
   solution_in_hex
 ) {
-  var bufferLength = 3024; // Sufficient buffer size for additional fields
-  var header = Buffer.alloc(bufferLength);
-  var position = 0;
-
-  // Version (4 bytes)
-  header.writeUInt32LE(version, position);
-  position += 4;
-
-  // Previous block hash (32 bytes)
-  Buffer.from(prevHashReversed, "hex").copy(header, position);
-  position += prevHashReversed.length / 2;
-
-  // Merkle root (32 bytes)
-  Buffer.from(merkleRootReversed, "hex").copy(header, position);
-  position += merkleRootReversed.length / 2;
-
-  // Final sapling root hash field (32 bytes)
-  Buffer.from(hashFinalSaplingRootReversed, "hex").copy(header, position);
-  position += hashFinalSaplingRootReversed.length / 2;
-
-  // Time (4 bytes)
-  header.writeUInt32LE(nTime, position); // Correctly parsing nTime as hex
-  position += 4;
-
-  // Bits, difficulty (4 bytes)
-  Buffer.from(difficulty_bits, "hex").copy(header, position);
-  position += 4;
-
-  // Nonce (32 bytes)
-  // Assuming nonce is correctly reversed if needed based on block version
-  const nonceBuffer = Buffer.from(nonce, "hex");
-  nonceBuffer.copy(header, position);
-  position += nonceBuffer.length; 
-
-  // Solution Compact Size and Value
-  const solutionBuffer = Buffer.from(solution_in_hex, "hex");
-  position += writeCompactSize(solutionBuffer.length, header, position);
-  solutionBuffer.copy(header, position);
-  position += solutionBuffer.length;
-
-  // Conditional handling for Version 5 specific fields
-  if (version >= 5) {
-    // PastelID (variable length)
-    const pastelIdBuffer = Buffer.from(pastelid_pubkey_in_hex, "hex");
-    // PastelID compact size
-    position += writeCompactSize(pastelIdBuffer.length, header, position);
-    pastelIdBuffer.copy(header, position);
-    position += pastelIdBuffer.length;
-
-    // Signature (variable length)
-    const signatureBuffer = Buffer.from(signature_in_hex, "hex"); 
-    position += writeCompactSize(signatureBuffer.length, header, position);
-    signatureBuffer.copy(header, position);
-    position += signatureBuffer.length;
-  }
-
-  // Trim the buffer to the actual used size
-  var trimmedHeader = header.slice(0, position);
-
-  return trimmedHeader;
-}
-
-function prepareForSerialization(parsedData) {
-  // Extract fields from v4_data_without_nonce_and_solution
-  const v4Data = parsedData.v4_data_without_nonce_and_solution;
-
-  // Extracting the version, previous hash, merkle root, and reserved hash from the v4 data
-  const version = parseInt(reverseHex(v4Data.substring(0, 8)), 16); // First 4 bytes for version
-  const prevHashReversed = v4Data.substring(8, 72); // Next 32 bytes for prev hash, assuming it's already reversed in v4 data
-  const merkleRootReversed = v4Data.substring(72, 136); // Following 32 bytes for merkle root, assuming it's already reversed
-  const hashFinalSaplingRootReversed = v4Data.substring(136, 200); // Following 32 bytes for Final Sapling Root, assuming it's already reversed
-  const nTime = parseInt(reverseHex(v4Data.substring(200, 208)), 16); // Convert to decimal
-  const difficultyBits = v4Data.substring(208, 216); // Next 4 bytes for difficulty bits
-
-  // Nonce handling - Convert from reversed hex to regular hex
-  const nonce = parsedData.nonce_value_in_hex;
-
-  // PastelID and Signature handling - Convert from hex to expected format for serialization
-  const pastelidPubkey = parsedData.pastelid_value_in_hex;
-  const signature = parsedData.signature_value_in_hex;
-  const solution = parsedData.solution_value_in_hex;
-
-  return {
-    nTime,
-    nonce,
+  const header = {
     version,
-    prevHashReversed,
-    merkleRootReversed,
-    hashFinalSaplingRootReversed,
-    difficultyBits,
-    pastelidPubkey,
-    signature,
-    solution
+    prevHash: prevHashReversed,
+    merkleRoot: merkleRootReversed,
+    finalSaplingRoot: hashFinalSaplingRootReversed,
+    time: nTime,
+    bits: Buffer.from(difficulty_bits, "hex").readUInt32LE(0),
+    nonce,
+    solution: solution_in_hex,
+    pastelId: Buffer.from(pastelid_pubkey_in_hex || "", "hex").toString("utf8"),
+    signature: signature_in_hex || "",
   };
-}
-
-function reverseHex(hex) {
-    return hex.match(/../g).reverse().join("");
-}
-
-function prepareForParsing(serializedHeader) {
-  let position = 0;
-  const output = {};
-
-  // Assuming the serializedHeader includes V4 data, nonce, solution, PastelID, and signature in that order
-
-  // V4 Data Without Nonce and Solution
-  const v4DataLength = 214; // Fixed length based on Zcash's format
-  output.v4_data_without_nonce_and_solution = serializedHeader
-    .slice(position, position + v4DataLength)
-    .toString("hex");
-  position += v4DataLength;
-
-  const nonceValue = serializedHeader
-    .slice(position, position + 32)
-    .toString("hex");
-  position += 32;
-  output.nonce_value_reversed = reverseHex(nonceValue);
-
-  // Solution Compact Size and Value
-  const solutionSize = readCompactSize(serializedHeader, position);
-  position += solutionSize.bytesRead;
-  output.solution_compact_size_hex = Buffer.alloc(getCompactSizeLength(solutionSize.value)); 
-  writeCompactSize(solutionSize.value, output.solution_compact_size_hex, 0);
-
-  const solutionValue = serializedHeader
-    .slice(position, position + solutionSize.value)
-    .toString("hex");
-  position += solutionSize.value;
-  output.solution_value = reverseHex(solutionValue); // Assuming solution needs reversal
-
-  // PastelID
-  const pastelIDSize = readCompactSize(serializedHeader, position); // Assuming compact size is 1 byte
-  position += pastelIDSize.bytesRead;
-  output.pastelid_value_in_hex = serializedHeader
-    .slice(position, position + pastelIDSize.value)
-    .toString("hex");
-  position += pastelIDSize.value;
-
-  // Signature
-  const signatureSize = readCompactSize(serializedHeader, position); // Assuming compact size is 1 byte
-  position += signatureSize.bytesRead;
-  output.signature_value = serializedHeader
-    .slice(position, position + signatureSize.value)
-    .toString("hex");
-
-  return output;
+  return Buffer.from(encodeHeader(header), "hex");
 }
 
 function SerializationTest(block_data_hex_string) {
-  // Show that the serializeHeader function effectively inverts the parseBlockData function:
-  const parsed_block_data = parseBlockData(block_data_hex_string);
-  const {
-    nTime,
-    nonce,
-    version,
-    prevHashReversed,
-    merkleRootReversed,
-    hashFinalSaplingRootReversed,
-    difficultyBits,
-    pastelidPubkey,
-    signature,
-    solution
-  } = prepareForSerialization(parsed_block_data);
-
-  const serialized_header = serializeHeader(
-    nTime,
-    nonce,
-    version,
-    prevHashReversed,
-    merkleRootReversed,
-    hashFinalSaplingRootReversed,
-    difficultyBits,
-    pastelidPubkey,
-    signature,
-    solution
-  );
-  const reparsed_data = prepareForParsing(serialized_header);
+  // Show that encodeHeader is the exact inverse of parseBlockHeader:
+  const parsed_block_data = parseBlockHeader(block_data_hex_string);
+  const serialized_header = encodeHeader(parsed_block_data);
+  const reparsed_data = parseHeader(serialized_header);
 
   console.log("Original data:");
   console.log(parsed_block_data);
   console.log("Serialized header:");
-  console.log(serialized_header.toString("hex"));
+  console.log(serialized_header);
   console.log("Reparsed data:");
   console.log(reparsed_data);
+  console.log(
+    `Round trip matches: ${
+      serialized_header ===
+      block_data_hex_string.substring(0, 2 * parsed_block_data.size)
+    }`
+  );
 }
 
 function runTests() {
@@ -474,6 +185,21 @@ function runTests() {
 
 module.exports = {
   is_validSolution,
+  is_valid_solution,
+  verifySolutionDetailed,
+  verifySolutionAsync,
+  HeaderContext,
+  setMaxInFlight,
+  verifyBatch,
+  solve,
+  indicesToMinimal,
+  indicesFromMinimal,
+  parseHeader,
+  parseBlockHeader,
+  encodeHeader,
   parseBlockData,
+  serializeHeader,
   getDataForEquihashValidation,
+  verifyRawHeader,
+  checkProofOfWork,
 };
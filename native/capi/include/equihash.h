@@ -0,0 +1,113 @@
+#ifndef EQUIHASH_H
+#define EQUIHASH_H
+
+/* Generated by cbindgen from equihash-capi. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The outcome of a call. The verification failures match `verify::Kind`.
+ */
+typedef enum EquihashStatus {
+  EQUIHASH_STATUS_OK = 0,
+  EQUIHASH_STATUS_INVALID_PARAMS = 1,
+  EQUIHASH_STATUS_COLLISION = 2,
+  EQUIHASH_STATUS_OUT_OF_ORDER = 3,
+  EQUIHASH_STATUS_DUPLICATE_IDXS = 4,
+  EQUIHASH_STATUS_NON_ZERO_ROOT_HASH = 5,
+  /**
+   * A required pointer was null, or an output buffer has the wrong length.
+   */
+  EQUIHASH_STATUS_INVALID_ARGUMENT = 6,
+  /**
+   * The header could not be parsed.
+   */
+  EQUIHASH_STATUS_INVALID_HEADER = 7,
+  /**
+   * The library panicked. This is a bug.
+   */
+  EQUIHASH_STATUS_INTERNAL_ERROR = 8,
+} EquihashStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Checks whether `soln` is a valid minimal solution for `input` followed by
+ * `nonce`.
+ *
+ * `personalization` is either null, for `"ZcashPoW"`, or 8 bytes. The nonce
+ * may instead be left at the end of `input`, with `nonce_len` 0.
+ *
+ * # Safety
+ *
+ * Every non-null pointer must be valid for reads of its length.
+ */
+enum EquihashStatus equihash_verify(uint32_t n,
+                                    uint32_t k,
+                                    const uint8_t *personalization,
+                                    const uint8_t *input,
+                                    size_t input_len,
+                                    const uint8_t *nonce,
+                                    size_t nonce_len,
+                                    const uint8_t *soln,
+                                    size_t soln_len);
+
+/**
+ * Parses a serialized Pastel header and checks its own solution with the
+ * parameters `(n, k)`, using Pastel's Equihash input layout.
+ *
+ * # Safety
+ *
+ * `header` must be valid for reads of `header_len` bytes.
+ */
+enum EquihashStatus equihash_verify_header(uint32_t n,
+                                           uint32_t k,
+                                           const uint8_t *header,
+                                           size_t header_len);
+
+/**
+ * Decodes a minimal solution into `indices_len` indices at `indices`, which
+ * must be exactly `equihash_solution_indices(n, k)`.
+ *
+ * # Safety
+ *
+ * `minimal` must be valid for reads of `minimal_len` bytes, and `indices`
+ * for writes of `indices_len` values.
+ */
+enum EquihashStatus equihash_indices_from_minimal(uint32_t n,
+                                                  uint32_t k,
+                                                  const uint8_t *minimal,
+                                                  size_t minimal_len,
+                                                  uint32_t *indices,
+                                                  size_t indices_len);
+
+/**
+ * The length in bytes of a minimal solution for `(n, k)`, or 0 if the
+ * parameters are not supported.
+ */
+size_t equihash_solution_len(uint32_t n, uint32_t k);
+
+/**
+ * The number of indices in a solution for `(n, k)`, or 0 if the parameters
+ * are not supported.
+ */
+size_t equihash_solution_indices(uint32_t n, uint32_t k);
+
+/**
+ * A static, NUL-terminated description of `status`, a value returned by one
+ * of the other functions. Values that are not an [`EquihashStatus`], such as
+ * codes added by a newer version of the library, get a generic message.
+ */
+const char *equihash_status_message(int status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* EQUIHASH_H */
@@ -0,0 +1,4 @@
+//! The C libraries: every `#[no_mangle]` function of `equihash_capi`, linked
+//! into a static and a shared library. Only built with the `capi` feature.
+
+pub use equihash_capi::*;
@@ -0,0 +1,412 @@
+//! The C API, built as `libequihash_capi.a` and `libequihash_capi.so` with
+//! the `capi` feature and declared in `include/equihash.h`. Regenerate the
+//! header from this directory after changing any signature:
+//!
+//! ```sh
+//! cbindgen --config cbindgen.toml --output include/equihash.h
+//! ```
+//!
+//! The verify and decode functions return an [`EquihashStatus`]. The sizing
+//! functions return a length, 0 for unsupported parameters, and
+//! `equihash_status_message` returns a static string. No function unwinds
+//! into the caller. Pointers may be null when their length is zero.
+
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use equihash::header::BlockHeader;
+use equihash::verify::{self, Kind, Params, ZCASH_PERSONALIZATION};
+
+/// The outcome of a call. The verification failures match `verify::Kind`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquihashStatus {
+    Ok = 0,
+    InvalidParams = 1,
+    Collision = 2,
+    OutOfOrder = 3,
+    DuplicateIdxs = 4,
+    NonZeroRootHash = 5,
+    /// A required pointer was null, or an output buffer has the wrong length.
+    InvalidArgument = 6,
+    /// The header could not be parsed.
+    InvalidHeader = 7,
+    /// The library panicked. This is a bug.
+    InternalError = 8,
+}
+
+impl EquihashStatus {
+    /// The status with the numeric value `code`, if there is one.
+    pub fn from_code(code: c_int) -> Option<Self> {
+        const ALL: [EquihashStatus; 9] = [
+            EquihashStatus::Ok,
+            EquihashStatus::InvalidParams,
+            EquihashStatus::Collision,
+            EquihashStatus::OutOfOrder,
+            EquihashStatus::DuplicateIdxs,
+            EquihashStatus::NonZeroRootHash,
+            EquihashStatus::InvalidArgument,
+            EquihashStatus::InvalidHeader,
+            EquihashStatus::InternalError,
+        ];
+        ALL.iter().copied().find(|&status| status as c_int == code)
+    }
+}
+
+impl From<Kind> for EquihashStatus {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::InvalidParams => EquihashStatus::InvalidParams,
+            Kind::Collision => EquihashStatus::Collision,
+            Kind::OutOfOrder => EquihashStatus::OutOfOrder,
+            Kind::DuplicateIdxs => EquihashStatus::DuplicateIdxs,
+            Kind::NonZeroRootHash => EquihashStatus::NonZeroRootHash,
+        }
+    }
+}
+
+impl From<Result<(), verify::Error>> for EquihashStatus {
+    fn from(result: Result<(), verify::Error>) -> Self {
+        match result {
+            Ok(()) => EquihashStatus::Ok,
+            Err(e) => e.kind().into(),
+        }
+    }
+}
+
+/// Runs `f`, turning a panic into `InternalError` rather than unwinding
+/// across the C boundary.
+fn guard<F: FnOnce() -> EquihashStatus>(f: F) -> EquihashStatus {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(EquihashStatus::InternalError)
+}
+
+/// Borrows `len` bytes at `ptr`, or `None` for a null pointer with a nonzero
+/// length.
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Reads the optional 8-byte personalization, defaulting to `"ZcashPoW"`.
+unsafe fn params(n: u32, k: u32, personalization: *const u8) -> Result<Params, EquihashStatus> {
+    let p = Params::new(n, k).map_err(|e| EquihashStatus::from(e.kind()))?;
+    let mut prefix = ZCASH_PERSONALIZATION;
+    if !personalization.is_null() {
+        prefix.copy_from_slice(slice::from_raw_parts(personalization, 8));
+    }
+    Ok(p.with_personalization(prefix))
+}
+
+/// Checks whether `soln` is a valid minimal solution for `input` followed by
+/// `nonce`.
+///
+/// `personalization` is either null, for `"ZcashPoW"`, or 8 bytes. The nonce
+/// may instead be left at the end of `input`, with `nonce_len` 0.
+///
+/// # Safety
+///
+/// Every non-null pointer must be valid for reads of its length.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn equihash_verify(
+    n: u32,
+    k: u32,
+    personalization: *const u8,
+    input: *const u8,
+    input_len: usize,
+    nonce: *const u8,
+    nonce_len: usize,
+    soln: *const u8,
+    soln_len: usize,
+) -> EquihashStatus {
+    guard(|| {
+        let p = match params(n, k, personalization) {
+            Ok(p) => p,
+            Err(status) => return status,
+        };
+        match (
+            bytes(input, input_len),
+            bytes(nonce, nonce_len),
+            bytes(soln, soln_len),
+        ) {
+            (Some(input), Some(nonce), Some(soln)) => {
+                verify::is_valid_solution_with_params(p, input, nonce, soln).into()
+            }
+            _ => EquihashStatus::InvalidArgument,
+        }
+    })
+}
+
+/// Parses a serialized Pastel header and checks its own solution with the
+/// parameters `(n, k)`, using Pastel's Equihash input layout.
+///
+/// # Safety
+///
+/// `header` must be valid for reads of `header_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn equihash_verify_header(
+    n: u32,
+    k: u32,
+    header: *const u8,
+    header_len: usize,
+) -> EquihashStatus {
+    guard(|| {
+        let header = match bytes(header, header_len) {
+            Some(header) => header,
+            None => return EquihashStatus::InvalidArgument,
+        };
+        match BlockHeader::parse(header) {
+            Ok(header) => header.verify_solution(n, k).into(),
+            Err(_) => EquihashStatus::InvalidHeader,
+        }
+    })
+}
+
+/// Decodes a minimal solution into `indices_len` indices at `indices`, which
+/// must be exactly `equihash_solution_indices(n, k)`.
+///
+/// # Safety
+///
+/// `minimal` must be valid for reads of `minimal_len` bytes, and `indices`
+/// for writes of `indices_len` values.
+#[no_mangle]
+pub unsafe extern "C" fn equihash_indices_from_minimal(
+    n: u32,
+    k: u32,
+    minimal: *const u8,
+    minimal_len: usize,
+    indices: *mut u32,
+    indices_len: usize,
+) -> EquihashStatus {
+    guard(|| {
+        let p = match Params::new(n, k) {
+            Ok(p) => p,
+            Err(e) => return e.kind().into(),
+        };
+        let minimal = match bytes(minimal, minimal_len) {
+            Some(minimal) => minimal,
+            None => return EquihashStatus::InvalidArgument,
+        };
+        if indices.is_null() || indices_len != p.solution_indices() {
+            return EquihashStatus::InvalidArgument;
+        }
+        match verify::indices_from_minimal(p, minimal) {
+            Ok(decoded) => {
+                slice::from_raw_parts_mut(indices, indices_len).copy_from_slice(&decoded);
+                EquihashStatus::Ok
+            }
+            Err(e) => e.kind().into(),
+        }
+    })
+}
+
+/// The length in bytes of a minimal solution for `(n, k)`, or 0 if the
+/// parameters are not supported.
+#[no_mangle]
+pub extern "C" fn equihash_solution_len(n: u32, k: u32) -> usize {
+    Params::new(n, k).map_or(0, |p| p.solution_len())
+}
+
+/// The number of indices in a solution for `(n, k)`, or 0 if the parameters
+/// are not supported.
+#[no_mangle]
+pub extern "C" fn equihash_solution_indices(n: u32, k: u32) -> usize {
+    Params::new(n, k).map_or(0, |p| p.solution_indices())
+}
+
+/// A static, NUL-terminated description of `status`, a value returned by one
+/// of the other functions. Values that are not an [`EquihashStatus`], such as
+/// codes added by a newer version of the library, get a generic message.
+#[no_mangle]
+pub extern "C" fn equihash_status_message(status: c_int) -> *const c_char {
+    let message = EquihashStatus::from_code(status).map_or(&b"unknown status\0"[..], message);
+    message.as_ptr() as *const c_char
+}
+
+fn message(status: EquihashStatus) -> &'static [u8] {
+    match status {
+        EquihashStatus::Ok => b"valid\0",
+        EquihashStatus::InvalidParams => b"invalid parameters\0",
+        EquihashStatus::Collision => b"invalid collision length between StepRows\0",
+        EquihashStatus::OutOfOrder => b"Index tree incorrectly ordered\0",
+        EquihashStatus::DuplicateIdxs => b"duplicate indices\0",
+        EquihashStatus::NonZeroRootHash => b"root hash of tree is non-zero\0",
+        EquihashStatus::InvalidArgument => b"invalid argument\0",
+        EquihashStatus::InvalidHeader => b"header could not be parsed\0",
+        EquihashStatus::InternalError => b"internal error\0",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        equihash_indices_from_minimal, equihash_solution_indices, equihash_solution_len,
+        equihash_status_message, equihash_verify, equihash_verify_header, EquihashStatus,
+    };
+    use equihash::header::BlockHeader;
+    use equihash::test_vectors::{INVALID_TEST_VECTORS, PASTEL_V5_BLOCK, VALID_TEST_VECTORS};
+    use equihash::verify::{indices_to_minimal, Kind, BGOLD_PERSONALIZATION};
+    use std::ffi::CStr;
+    use std::os::raw::c_int;
+    use std::ptr;
+
+    unsafe fn verify(
+        n: u32,
+        k: u32,
+        personalization: *const u8,
+        input: &[u8],
+        nonce: &[u8],
+        soln: &[u8],
+    ) -> EquihashStatus {
+        equihash_verify(
+            n,
+            k,
+            personalization,
+            input.as_ptr(),
+            input.len(),
+            nonce.as_ptr(),
+            nonce.len(),
+            soln.as_ptr(),
+            soln.len(),
+        )
+    }
+
+    #[test]
+    fn matches_test_vectors() {
+        for tv in VALID_TEST_VECTORS {
+            let (n, k) = (tv.params.n(), tv.params.k());
+            for soln in tv.solutions {
+                let minimal = indices_to_minimal(tv.params, soln).unwrap();
+                unsafe {
+                    assert_eq!(
+                        verify(n, k, ptr::null(), tv.input, &tv.nonce, &minimal),
+                        EquihashStatus::Ok
+                    );
+                    assert_ne!(
+                        verify(
+                            n,
+                            k,
+                            BGOLD_PERSONALIZATION.as_ptr(),
+                            tv.input,
+                            &tv.nonce,
+                            &minimal
+                        ),
+                        EquihashStatus::Ok
+                    );
+
+                    let mut indices = vec![0; equihash_solution_indices(n, k)];
+                    let status = equihash_indices_from_minimal(
+                        n,
+                        k,
+                        minimal.as_ptr(),
+                        minimal.len(),
+                        indices.as_mut_ptr(),
+                        indices.len(),
+                    );
+                    assert_eq!(status, EquihashStatus::Ok);
+                    assert_eq!(&indices[..], *soln);
+                }
+            }
+        }
+
+        for tv in INVALID_TEST_VECTORS {
+            let (n, k) = (tv.params.n(), tv.params.k());
+            let minimal = indices_to_minimal(tv.params, tv.solution).unwrap();
+            let status = unsafe { verify(n, k, ptr::null(), tv.input, &tv.nonce, &minimal) };
+            assert_eq!(status, EquihashStatus::from(tv.error));
+        }
+    }
+
+    #[test]
+    fn pastel_header() {
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (_, size) = BlockHeader::read(&block).unwrap();
+        unsafe {
+            assert_eq!(
+                equihash_verify_header(200, 9, block.as_ptr(), size),
+                EquihashStatus::Ok
+            );
+            assert_eq!(
+                equihash_verify_header(200, 9, block.as_ptr(), block.len()),
+                EquihashStatus::InvalidHeader
+            );
+            assert_eq!(
+                equihash_verify_header(144, 5, block.as_ptr(), size),
+                EquihashStatus::InvalidParams
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let tv = &VALID_TEST_VECTORS[0];
+        let (n, k) = (tv.params.n(), tv.params.k());
+        let minimal = indices_to_minimal(tv.params, tv.solutions[0]).unwrap();
+        assert_eq!(equihash_solution_len(n, k), minimal.len());
+        assert_eq!(equihash_solution_len(200, 8), 0);
+
+        unsafe {
+            let status = equihash_verify(
+                n,
+                k,
+                ptr::null(),
+                ptr::null(),
+                1,
+                ptr::null(),
+                0,
+                minimal.as_ptr(),
+                minimal.len(),
+            );
+            assert_eq!(status, EquihashStatus::InvalidArgument);
+            assert_eq!(
+                verify(200, 8, ptr::null(), tv.input, &tv.nonce, &minimal),
+                EquihashStatus::InvalidParams
+            );
+            assert_eq!(
+                equihash_verify_header(200, 9, ptr::null(), 0),
+                EquihashStatus::InvalidHeader
+            );
+
+            let mut indices = vec![0; tv.solutions[0].len() - 1];
+            let status = equihash_indices_from_minimal(
+                n,
+                k,
+                minimal.as_ptr(),
+                minimal.len(),
+                indices.as_mut_ptr(),
+                indices.len(),
+            );
+            assert_eq!(status, EquihashStatus::InvalidArgument);
+        }
+    }
+
+    #[test]
+    fn status_messages() {
+        assert_eq!(
+            Kind::Collision.to_string(),
+            message(EquihashStatus::Collision)
+        );
+        assert_eq!(
+            Kind::OutOfOrder.to_string(),
+            message(EquihashStatus::OutOfOrder)
+        );
+        assert_eq!(message(EquihashStatus::Ok), "valid");
+
+        for code in [-1, 9, c_int::MAX] {
+            assert_eq!(EquihashStatus::from_code(code), None);
+            let message = unsafe { CStr::from_ptr(equihash_status_message(code)) };
+            assert_eq!(message.to_str().unwrap(), "unknown status");
+        }
+    }
+
+    fn message(status: EquihashStatus) -> String {
+        let message = unsafe { CStr::from_ptr(equihash_status_message(status as c_int)) };
+        message.to_str().unwrap().to_string()
+    }
+}
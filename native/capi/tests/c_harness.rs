@@ -0,0 +1,116 @@
+//! Compiles `harness.c` against the generated header and the static library,
+//! then checks its output on the test vectors.
+
+use equihash::header::BlockHeader;
+use equihash::test_vectors::{INVALID_TEST_VECTORS, PASTEL_V5_BLOCK, VALID_TEST_VECTORS};
+use equihash::verify::indices_to_minimal;
+use equihash_capi::EquihashStatus;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The directory holding the library: `cargo test` builds the
+/// `equihash_capi` example in `examples`, next to the `deps` directory holding
+/// this test. The harness links the static archive, so a stale shared library
+/// elsewhere on the loader path can't be picked up at run time.
+fn library_dir() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().join("examples")
+}
+
+fn build_harness() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = library_dir();
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("equihash_harness");
+
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(cc)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/harness.c"))
+        .arg("-o")
+        .arg(&out)
+        .arg(lib_dir.join("libequihash_capi.a"))
+        .args(["-lpthread", "-ldl", "-lm"])
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "harness.c failed to build");
+    out
+}
+
+fn run(harness: &Path, args: &[String]) -> String {
+    let output = Command::new(harness).args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "harness {:?} failed: {:?}",
+        args,
+        output
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn status_line(status: EquihashStatus, message: &str) -> String {
+    format!("{} {}\n", status as i32, message)
+}
+
+#[test]
+fn c_harness() {
+    let harness = build_harness();
+
+    for tv in VALID_TEST_VECTORS.iter().take(4) {
+        let soln = tv.solutions[0];
+        let minimal = indices_to_minimal(tv.params, soln).unwrap();
+        let args = [
+            "verify".to_string(),
+            tv.params.n().to_string(),
+            tv.params.k().to_string(),
+            hex::encode(tv.input),
+            hex::encode(tv.nonce),
+            hex::encode(&minimal),
+        ];
+        let indices: Vec<String> = soln.iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            run(&harness, &args),
+            status_line(EquihashStatus::Ok, "valid") + &indices.join(" ") + "\n"
+        );
+    }
+
+    for tv in INVALID_TEST_VECTORS {
+        let minimal = indices_to_minimal(tv.params, tv.solution).unwrap();
+        let args = [
+            "verify".to_string(),
+            tv.params.n().to_string(),
+            tv.params.k().to_string(),
+            hex::encode(tv.input),
+            hex::encode(tv.nonce),
+            hex::encode(&minimal),
+        ];
+        assert_eq!(
+            run(&harness, &args),
+            status_line(tv.error.into(), &tv.error.to_string())
+        );
+    }
+
+    let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+    let (_, size) = BlockHeader::read(&block).unwrap();
+    let header = |n: u32, k: u32, data: &[u8]| {
+        let args = [
+            "header".to_string(),
+            n.to_string(),
+            k.to_string(),
+            hex::encode(data),
+        ];
+        run(&harness, &args)
+    };
+    assert_eq!(
+        header(200, 9, &block[..size]),
+        status_line(EquihashStatus::Ok, "valid")
+    );
+    assert_eq!(
+        header(200, 9, &block),
+        status_line(EquihashStatus::InvalidHeader, "header could not be parsed")
+    );
+}
@@ -0,0 +1,86 @@
+/* Drives the C API from the command line for tests/c_harness.rs:
+ *
+ *   harness verify N K INPUT NONCE SOLUTION
+ *   harness header N K HEADER
+ *
+ * Arguments are hex. Prints the status code and message, then the decoded
+ * indices of a valid solution. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "equihash.h"
+
+static uint8_t *decode_hex(const char *hex, size_t *len) {
+    size_t digits = strlen(hex);
+    uint8_t *out = malloc(digits / 2 + 1);
+    if (out == NULL || digits % 2 != 0) {
+        fprintf(stderr, "bad hex: %s\n", hex);
+        exit(2);
+    }
+    for (size_t i = 0; i < digits / 2; i++) {
+        unsigned int byte;
+        if (sscanf(hex + 2 * i, "%2x", &byte) != 1) {
+            fprintf(stderr, "bad hex: %s\n", hex);
+            exit(2);
+        }
+        out[i] = (uint8_t)byte;
+    }
+    *len = digits / 2;
+    return out;
+}
+
+static void print_status(EquihashStatus status) {
+    printf("%d %s\n", (int)status, equihash_status_message((int)status));
+}
+
+static int verify(uint32_t n, uint32_t k, char **argv) {
+    size_t input_len, nonce_len, soln_len;
+    uint8_t *input = decode_hex(argv[0], &input_len);
+    uint8_t *nonce = decode_hex(argv[1], &nonce_len);
+    uint8_t *soln = decode_hex(argv[2], &soln_len);
+
+    EquihashStatus status =
+        equihash_verify(n, k, NULL, input, input_len, nonce, nonce_len, soln, soln_len);
+    print_status(status);
+
+    if (status == EQUIHASH_STATUS_OK) {
+        size_t count = equihash_solution_indices(n, k);
+        uint32_t *indices = malloc(count * sizeof(uint32_t));
+        status = equihash_indices_from_minimal(n, k, soln, soln_len, indices, count);
+        if (status != EQUIHASH_STATUS_OK) {
+            print_status(status);
+            return 1;
+        }
+        for (size_t i = 0; i < count; i++) {
+            printf(i == 0 ? "%u" : " %u", indices[i]);
+        }
+        printf("\n");
+        free(indices);
+    }
+
+    free(input);
+    free(nonce);
+    free(soln);
+    return 0;
+}
+
+static int header(uint32_t n, uint32_t k, char **argv) {
+    size_t header_len;
+    uint8_t *header = decode_hex(argv[0], &header_len);
+    print_status(equihash_verify_header(n, k, header, header_len));
+    free(header);
+    return 0;
+}
+
+int main(int argc, char **argv) {
+    if (argc == 7 && strcmp(argv[1], "verify") == 0) {
+        return verify(atoi(argv[2]), atoi(argv[3]), argv + 4);
+    }
+    if (argc == 5 && strcmp(argv[1], "header") == 0) {
+        return header(atoi(argv[2]), atoi(argv[3]), argv + 4);
+    }
+    fprintf(stderr, "usage: harness verify N K INPUT NONCE SOLUTION | header N K HEADER\n");
+    return 2;
+}
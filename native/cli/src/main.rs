@@ -0,0 +1,401 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use equihash::header::BlockHeader;
+use equihash::pow;
+use equihash::verify::{
+    self, Kind, Params, BGOLD_PERSONALIZATION, BITCOINZ_PERSONALIZATION, PERSONALIZATION_PRESETS,
+    ZCASH_PERSONALIZATION,
+};
+use std::convert::TryInto;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+
+/// Bad hex, an unreadable file or a header that does not parse.
+const EXIT_INPUT_ERROR: i32 = 1;
+/// The header's hash does not meet its target.
+const EXIT_POW_FAILED: i32 = 8;
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  valid
+  1  unreadable input or header
+  2  usage error
+  3  INVALID_PARAMS
+  4  COLLISION
+  5  OUT_OF_ORDER
+  6  DUPLICATE_IDXS
+  7  NON_ZERO_ROOT_HASH
+  8  proof of work failed (header only)
+
+INPUT is a hex string, @PATH to read a file, or - to read stdin.";
+
+#[derive(Parser)]
+#[command(name = "equihash", version, about = "Verifies Equihash solutions", after_help = EXIT_CODES)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Verify a solution for a header and nonce
+    #[command(after_help = EXIT_CODES)]
+    Verify(VerifyArgs),
+    /// Parse a raw Pastel header or block and verify its solution and proof of work
+    #[command(after_help = EXIT_CODES)]
+    Header(HeaderArgs),
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Use this coin's parameters and personalization
+    #[arg(long, value_enum, conflicts_with_all = ["n", "k", "personalization"])]
+    coin: Option<Coin>,
+    /// Equihash n [default: 200]
+    #[arg(short, long, requires = "k")]
+    n: Option<u32>,
+    /// Equihash k [default: 9]
+    #[arg(short, long, requires = "n")]
+    k: Option<u32>,
+    /// A preset name (zcash, bgold, bitcoinz) or the 8-character prefix
+    #[arg(long, default_value = "zcash")]
+    personalization: String,
+    /// The header, without the nonce
+    #[arg(long, value_name = "INPUT")]
+    header: String,
+    /// The nonce, if not already at the end of the header
+    #[arg(long, value_name = "INPUT")]
+    nonce: Option<String>,
+    /// The solution in minimal encoding
+    #[arg(long, value_name = "INPUT")]
+    solution: String,
+    /// Read files and stdin as raw bytes rather than hex
+    #[arg(long)]
+    raw: bool,
+}
+
+#[derive(Args)]
+struct HeaderArgs {
+    /// The serialized header, or a block starting with one
+    #[arg(value_name = "INPUT")]
+    input: String,
+    /// Equihash n
+    #[arg(short, long, default_value_t = 200)]
+    n: u32,
+    /// Equihash k
+    #[arg(short, long, default_value_t = 9)]
+    k: u32,
+    /// The network's proof-of-work limit in compact form, as hex
+    #[arg(long, value_name = "BITS", value_parser = parse_bits, default_value = "2007ffff")]
+    pow_limit: u32,
+    /// Read files and stdin as raw bytes rather than hex
+    #[arg(long)]
+    raw: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Coin {
+    Pastel,
+    Zcash,
+    Bgold,
+    Bitcoinz,
+}
+
+impl Coin {
+    fn params(self) -> Params {
+        let (n, k, personalization) = match self {
+            Coin::Pastel | Coin::Zcash => (200, 9, ZCASH_PERSONALIZATION),
+            Coin::Bgold => (144, 5, BGOLD_PERSONALIZATION),
+            Coin::Bitcoinz => (144, 5, BITCOINZ_PERSONALIZATION),
+        };
+        Params::new(n, k)
+            .expect("preset parameters are valid")
+            .with_personalization(personalization)
+    }
+}
+
+fn parse_bits(s: &str) -> Result<u32, String> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    u32::from_str_radix(s, 16).map_err(|e| e.to_string())
+}
+
+fn parse_personalization(s: &str) -> Result<[u8; 8], String> {
+    if let Some((_, preset)) = PERSONALIZATION_PRESETS.iter().find(|(name, _)| *name == s) {
+        return Ok(*preset);
+    }
+    s.as_bytes().try_into().map_err(|_| {
+        format!(
+            "personalization: expected a preset name or 8 bytes, got {:?}",
+            s
+        )
+    })
+}
+
+/// Reads command-line inputs, allowing at most one of them to come from stdin.
+struct Inputs {
+    raw: bool,
+    stdin_used: bool,
+}
+
+impl Inputs {
+    fn new(raw: bool) -> Self {
+        Inputs {
+            raw,
+            stdin_used: false,
+        }
+    }
+
+    fn read(&mut self, name: &str, spec: &str) -> Result<Vec<u8>, String> {
+        let data = if spec == "-" {
+            if self.stdin_used {
+                return Err(format!("{}: stdin has already been read", name));
+            }
+            self.stdin_used = true;
+            let mut data = Vec::new();
+            io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|e| format!("{}: stdin: {}", name, e))?;
+            data
+        } else if let Some(path) = spec.strip_prefix('@') {
+            fs::read(path).map_err(|e| format!("{}: {}: {}", name, path, e))?
+        } else {
+            return decode_hex(name, spec.as_bytes());
+        };
+
+        if self.raw {
+            Ok(data)
+        } else {
+            decode_hex(name, &data)
+        }
+    }
+}
+
+/// Decodes hex, ignoring surrounding whitespace and an optional `0x` prefix.
+fn decode_hex(name: &str, text: &[u8]) -> Result<Vec<u8>, String> {
+    let text = std::str::from_utf8(text)
+        .map_err(|_| format!("{}: not valid hex", name))?
+        .trim();
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    hex::decode(text).map_err(|e| format!("{}: {}", name, e))
+}
+
+fn exit_code(kind: Kind) -> i32 {
+    match kind {
+        Kind::InvalidParams => 3,
+        Kind::Collision => 4,
+        Kind::OutOfOrder => 5,
+        Kind::DuplicateIdxs => 6,
+        Kind::NonZeroRootHash => 7,
+    }
+}
+
+/// Describes a verification result in one line.
+fn describe(result: &Result<(), verify::Error>) -> String {
+    match result {
+        Ok(()) => "valid".to_string(),
+        Err(e) => {
+            let mut line = format!("{}: {}", e.kind().code(), e.kind());
+            if let Some(location) = e.location() {
+                line.push_str(&format!(" at {}", location));
+            }
+            line
+        }
+    }
+}
+
+fn verify(args: VerifyArgs) -> Result<i32, String> {
+    let p = match args.coin {
+        Some(coin) => coin.params(),
+        None => match Params::new(args.n.unwrap_or(200), args.k.unwrap_or(9)) {
+            Ok(p) => p.with_personalization(parse_personalization(&args.personalization)?),
+            Err(e) => {
+                println!("{}", describe(&Err(e)));
+                return Ok(exit_code(Kind::InvalidParams));
+            }
+        },
+    };
+
+    let mut inputs = Inputs::new(args.raw);
+    let header = inputs.read("header", &args.header)?;
+    let nonce = match &args.nonce {
+        Some(nonce) => inputs.read("nonce", nonce)?,
+        None => Vec::new(),
+    };
+    let solution = inputs.read("solution", &args.solution)?;
+
+    let result = verify::is_valid_solution_with_params(p, &header, &nonce, &solution);
+    println!("{}", describe(&result));
+    Ok(result.map_or_else(|e| exit_code(e.kind()), |()| 0))
+}
+
+fn header(args: HeaderArgs) -> Result<i32, String> {
+    let data = Inputs::new(args.raw).read("header", &args.input)?;
+    let (header, _) = BlockHeader::read(&data).map_err(|e| format!("header: {}", e))?;
+
+    // Hashes are shown byte-reversed, as the node displays them.
+    let mut hash = header.hash();
+    hash.reverse();
+    println!("hash:          {}", hex::encode(hash));
+    println!("version:       {}", header.version);
+    println!("time:          {}", header.time);
+    println!("bits:          {:08x}", header.bits);
+
+    let solution = header.verify_solution(args.n, args.k);
+    println!("equihash:      {}", describe(&solution));
+
+    let work = pow::check_proof_of_work(&header.hash(), header.bits, args.pow_limit);
+    match work {
+        Ok(()) => println!("proof of work: valid"),
+        Err(e) => println!("proof of work: {}: {}", e.code(), e),
+    }
+
+    Ok(match (solution, work) {
+        (Err(e), _) => exit_code(e.kind()),
+        (Ok(()), Err(_)) => EXIT_POW_FAILED,
+        (Ok(()), Ok(())) => 0,
+    })
+}
+
+fn main() {
+    let result = match Cli::parse().command {
+        Command::Verify(args) => verify(args),
+        Command::Header(args) => header(args),
+    };
+    let code = result.unwrap_or_else(|e| {
+        eprintln!("equihash: {}", e);
+        EXIT_INPUT_ERROR
+    });
+    process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_hex, exit_code, header, parse_personalization, verify, Cli, Coin, HeaderArgs,
+        Inputs, VerifyArgs, EXIT_POW_FAILED,
+    };
+    use clap::CommandFactory;
+    use equihash::test_vectors::{INVALID_TEST_VECTORS, PASTEL_V5_BLOCK, VALID_TEST_VECTORS};
+    use equihash::verify::{indices_to_minimal, Kind, Params, BGOLD_PERSONALIZATION};
+
+    fn verify_args(p: Params, input: &[u8], nonce: &[u8], indices: &[u32]) -> VerifyArgs {
+        VerifyArgs {
+            coin: None,
+            n: Some(p.n()),
+            k: Some(p.k()),
+            personalization: "zcash".to_string(),
+            header: hex::encode(input),
+            nonce: Some(hex::encode(nonce)),
+            solution: hex::encode(indices_to_minimal(p, indices).unwrap()),
+            raw: false,
+        }
+    }
+
+    fn header_args(input: String) -> HeaderArgs {
+        HeaderArgs {
+            input,
+            n: 200,
+            k: 9,
+            pow_limit: 0x2007ffff,
+            raw: false,
+        }
+    }
+
+    #[test]
+    fn cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let mut codes: Vec<i32> = [
+            Kind::InvalidParams,
+            Kind::Collision,
+            Kind::OutOfOrder,
+            Kind::DuplicateIdxs,
+            Kind::NonZeroRootHash,
+        ]
+        .iter()
+        .map(|&kind| exit_code(kind))
+        .collect();
+        codes.extend_from_slice(&[0, 1, 2, EXIT_POW_FAILED]);
+        let len = codes.len();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), len);
+    }
+
+    #[test]
+    fn inputs() {
+        assert_eq!(decode_hex("x", b" 0x00ff\n").unwrap(), vec![0x00, 0xff]);
+        assert!(decode_hex("x", b"0g").is_err());
+        assert!(decode_hex("x", &[0xff]).is_err());
+
+        let mut inputs = Inputs::new(true);
+        // Literals are always hex, even with --raw.
+        assert_eq!(inputs.read("x", "abcd").unwrap(), vec![0xab, 0xcd]);
+        assert!(inputs.read("x", "@/nonexistent").is_err());
+    }
+
+    #[test]
+    fn personalization() {
+        assert_eq!(
+            parse_personalization("bgold").unwrap(),
+            BGOLD_PERSONALIZATION
+        );
+        assert_eq!(parse_personalization("TestPoW!").unwrap(), *b"TestPoW!");
+        assert!(parse_personalization("short").is_err());
+    }
+
+    #[test]
+    fn coin_params() {
+        let p = Coin::Bgold.params();
+        assert_eq!((p.n(), p.k()), (144, 5));
+        let p = Coin::Pastel.params();
+        assert_eq!((p.n(), p.k()), (200, 9));
+    }
+
+    #[test]
+    fn valid_test_vectors() {
+        for tv in VALID_TEST_VECTORS {
+            for soln in tv.solutions {
+                let args = verify_args(tv.params, tv.input, &tv.nonce, soln);
+                assert_eq!(verify(args), Ok(0));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_test_vectors() {
+        for tv in INVALID_TEST_VECTORS {
+            let expected = match tv.error {
+                Kind::Collision => 4,
+                Kind::OutOfOrder => 5,
+                Kind::DuplicateIdxs => 6,
+                Kind::NonZeroRootHash => 7,
+                Kind::InvalidParams => unreachable!("the vectors have valid parameters"),
+            };
+            let args = verify_args(tv.params, tv.input, &tv.nonce, tv.solution);
+            assert_eq!(verify(args), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn pastel_block() {
+        assert_eq!(header(header_args(PASTEL_V5_BLOCK.to_string())), Ok(0));
+
+        // A limit below the block's target fails only the proof of work.
+        let mut args = header_args(PASTEL_V5_BLOCK.to_string());
+        args.pow_limit = 0x1d00ffff;
+        assert_eq!(header(args), Ok(EXIT_POW_FAILED));
+        assert_eq!(EXIT_POW_FAILED, 8);
+
+        // The solution starts after the 140-byte header and its 3-byte length.
+        let mut block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        block[143] ^= 1;
+        assert_eq!(header(header_args(hex::encode(&block))), Ok(4));
+
+        let args = header_args(PASTEL_V5_BLOCK[..200].to_string());
+        assert!(header(args).is_err());
+    }
+}
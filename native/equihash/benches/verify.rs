@@ -0,0 +1,128 @@
+//! Times verification of the valid test vectors. Run from `native/` with
+//! `cargo bench -p equihash --features test-vectors` to compare hashing each
+//! Blake2b output once against hashing it for every index, and add `parallel`
+//! to the features to compare split trees against a single thread.
+
+use equihash::test_vectors::VALID_TEST_VECTORS;
+use equihash::verify::{
+    indices_to_minimal, is_valid_solution_per_index, is_valid_solution_with_params, Params,
+};
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 100;
+const TRIALS: u32 = 6;
+
+/// A solution in minimal form, with the input and nonce it solves.
+struct Case {
+    input: &'static [u8],
+    nonce: [u8; 32],
+    soln: Vec<u8>,
+    /// The number of distinct Blake2b outputs its indices are cut from.
+    outputs: usize,
+}
+
+/// The valid test vectors grouped by parameters, in the order they first
+/// appear.
+fn cases() -> Vec<(Params, Vec<Case>)> {
+    let mut groups: Vec<(Params, Vec<Case>)> = Vec::new();
+    for tv in VALID_TEST_VECTORS {
+        let i = match groups.iter().position(|(p, _)| *p == tv.params) {
+            Some(i) => i,
+            None => {
+                groups.push((tv.params, Vec::new()));
+                groups.len() - 1
+            }
+        };
+        let per_output = 512 / tv.params.n();
+        for soln in tv.solutions {
+            let mut blocks: Vec<u32> = soln.iter().map(|i| i / per_output).collect();
+            blocks.sort_unstable();
+            blocks.dedup();
+            groups[i].1.push(Case {
+                input: tv.input,
+                nonce: tv.nonce,
+                soln: indices_to_minimal(tv.params, soln).unwrap(),
+                outputs: blocks.len(),
+            });
+        }
+    }
+    groups
+}
+
+fn verify_all(p: Params, cases: &[Case]) {
+    for case in cases {
+        is_valid_solution_with_params(p, case.input, &case.nonce, &case.soln).unwrap();
+    }
+}
+
+fn verify_all_per_index(p: Params, cases: &[Case]) {
+    for case in cases {
+        is_valid_solution_per_index(p, case.input, &case.nonce, &case.soln).unwrap();
+    }
+}
+
+/// The time per solution of `f`, which verifies every case once: the best of
+/// several trials, after a warm-up round.
+fn time<F: FnMut()>(cases: usize, mut f: F) -> Duration {
+    f();
+    let mut best = Duration::MAX;
+    for _ in 0..TRIALS {
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            f();
+        }
+        best = best.min(start.elapsed());
+    }
+    best / (ROUNDS * cases as u32)
+}
+
+/// Reports, for each set of parameters, how many Blake2b outputs the
+/// solutions need against how many indices they have, and the time with each
+/// output hashed once against once per index.
+fn hash_output_sharing(p: Params, cases: &[Case]) {
+    let indices = cases.len() * p.solution_indices();
+    let outputs: usize = cases.iter().map(|case| case.outputs).sum();
+    let per_index = time(cases.len(), || verify_all_per_index(p, cases));
+    let shared = time(cases.len(), || verify_all(p, cases));
+    println!(
+        "{},{}: {} solutions, {} outputs for {} indices; {:?} per index, {:?} shared ({:.2}x)",
+        p.n(),
+        p.k(),
+        cases.len(),
+        outputs,
+        indices,
+        per_index,
+        shared,
+        per_index.as_secs_f64() / shared.as_secs_f64()
+    );
+}
+
+#[cfg(not(feature = "parallel"))]
+fn main() {
+    for (p, cases) in cases() {
+        hash_output_sharing(p, &cases);
+    }
+}
+
+/// Also runs each verification inside a one-thread pool, where the split
+/// halves of a tree are validated one after the other.
+#[cfg(feature = "parallel")]
+fn main() {
+    let one_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    for (p, cases) in cases() {
+        hash_output_sharing(p, &cases);
+        let sequential = time(cases.len(), || one_thread.install(|| verify_all(p, &cases)));
+        let split = time(cases.len(), || verify_all(p, &cases));
+        println!(
+            "{},{}: {:?} on one thread, {:?} split ({:.2}x)",
+            p.n(),
+            p.k(),
+            sequential,
+            split,
+            sequential.as_secs_f64() / split.as_secs_f64()
+        );
+    }
+}
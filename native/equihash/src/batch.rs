@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use blake2b_simd::State as Blake2bState;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::verify::{initialise_state, is_valid_solution_with_state, Error, Params};
+
+/// One solution to check as part of a batch.
+pub struct BatchItem {
+    pub n: u32,
+    pub k: u32,
+    pub personalization: [u8; 8],
+    pub input: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub soln: Vec<u8>,
+}
+
+/// Checks every item in `items` across the rayon thread pool, returning the
+/// results in input order. Without the `rayon` feature the items are checked
+/// one after another.
+///
+/// The Blake2b base state is built once for each distinct set of parameters
+/// and shared by all items using it.
+pub fn verify_batch(items: &[BatchItem]) -> Vec<Result<(), Error>> {
+    let key = |item: &BatchItem| (item.n, item.k, item.personalization);
+    let mut states: HashMap<_, Result<(Params, Blake2bState), Error>> = HashMap::new();
+    for item in items {
+        states.entry(key(item)).or_insert_with(|| {
+            let p = Params::new(item.n, item.k)?.with_personalization(item.personalization);
+            Ok((p, initialise_state(&p)))
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    let items = items.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let items = items.iter();
+
+    items
+        .map(|item| match &states[&key(item)] {
+            Ok((p, state)) => {
+                is_valid_solution_with_state(*p, state, &item.input, &item.nonce, &item.soln)
+            }
+            Err(e) => Err(Error::from(e.kind())),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_batch, BatchItem};
+    use crate::test_vectors::VALID_TEST_VECTORS;
+    use crate::verify::{indices_to_minimal, Kind, BGOLD_PERSONALIZATION, ZCASH_PERSONALIZATION};
+
+    #[test]
+    fn results_in_input_order() {
+        let tv = &VALID_TEST_VECTORS[0];
+        let (n, k) = (tv.params.n(), tv.params.k());
+        let soln = indices_to_minimal(tv.params, tv.solutions[0]).unwrap();
+        let mut mutated = soln.clone();
+        mutated[0] ^= 1;
+
+        let item = |n, k, soln: &[u8]| BatchItem {
+            n,
+            k,
+            personalization: ZCASH_PERSONALIZATION,
+            input: tv.input.to_vec(),
+            nonce: tv.nonce.to_vec(),
+            soln: soln.to_vec(),
+        };
+        let items = vec![
+            item(n, k, &soln),
+            item(n, k, &mutated),
+            item(n, k - 1, &soln),
+            item(n, k, &soln),
+            BatchItem {
+                personalization: BGOLD_PERSONALIZATION,
+                ..item(n, k, &soln)
+            },
+        ];
+
+        let results = verify_batch(&items);
+        assert_eq!(results.len(), 5);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap_err().kind(), Kind::InvalidParams);
+        assert!(results[3].is_ok());
+        assert!(results[4].is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use blake2b_simd::State as Blake2bState;
+
+use crate::verify::{initialise_state, is_valid_solution_with_state, Error, Params};
+
+/// The Blake2b midstate of one header, for checking many `(nonce, solution)`
+/// pairs against it.
+///
+/// Pool shares for the same job differ only in the nonce and solution, so the
+/// header is absorbed once here and each check starts from a copy of the
+/// state instead of rehashing it.
+#[derive(Clone)]
+pub struct HeaderContext {
+    p: Params,
+    state: Blake2bState,
+}
+
+impl HeaderContext {
+    /// Absorbs `input`, the header without its nonce, under the parameters `p`.
+    pub fn new(p: Params, input: &[u8]) -> Self {
+        let mut state = initialise_state(&p);
+        state.update(input);
+        HeaderContext { p, state }
+    }
+
+    pub fn params(&self) -> Params {
+        self.p
+    }
+
+    /// Checks whether `soln` is a valid solution for the header followed by
+    /// `nonce`. Equivalent to [`crate::verify::is_valid_solution_with_params`]
+    /// with the same header.
+    pub fn verify(&self, nonce: &[u8], soln: &[u8]) -> Result<(), Error> {
+        is_valid_solution_with_state(self.p, &self.state, &[], nonce, soln)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HeaderContext;
+    use crate::test_vectors::{INVALID_TEST_VECTORS, VALID_TEST_VECTORS};
+    use crate::verify::indices_to_minimal;
+
+    #[test]
+    fn matches_full_verification() {
+        for tv in VALID_TEST_VECTORS {
+            let ctx = HeaderContext::new(tv.params, tv.input);
+            for soln in tv.solutions {
+                let minimal = indices_to_minimal(tv.params, soln).unwrap();
+                ctx.verify(&tv.nonce, &minimal).unwrap();
+
+                // The midstate must not have been consumed by the first check.
+                ctx.verify(&tv.nonce, &minimal).unwrap();
+
+                let mut nonce = tv.nonce;
+                nonce[0] ^= 1;
+                ctx.verify(&nonce, &minimal).unwrap_err();
+            }
+        }
+
+        for tv in INVALID_TEST_VECTORS {
+            let ctx = HeaderContext::new(tv.params, tv.input);
+            let minimal = indices_to_minimal(tv.params, tv.solution).unwrap();
+            assert_eq!(
+                ctx.verify(&tv.nonce, &minimal).unwrap_err().kind(),
+                tv.error
+            );
+        }
+    }
+}
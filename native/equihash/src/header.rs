@@ -0,0 +1,345 @@
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{Cursor, Read};
+
+use crate::verify;
+
+/// The first header version that carries a PastelID and signature.
+pub const SIGNED_BLOCK_VERSION: u32 = 5;
+
+/// The largest length prefix accepted for a variable-length field, matching
+/// `MAX_SIZE` in the node's serializer.
+const MAX_SIZE: u64 = 0x0200_0000;
+
+/// A Pastel block header.
+///
+/// Hashes are kept in their serialized byte order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub version: u32,
+    pub prev_hash: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub final_sapling_root: [u8; 32],
+    pub time: u32,
+    pub bits: u32,
+    pub nonce: [u8; 32],
+    pub solution: Vec<u8>,
+    /// Only serialized from [`SIGNED_BLOCK_VERSION`] on; empty before that.
+    pub pastel_id: String,
+    /// Only serialized from [`SIGNED_BLOCK_VERSION`] on; empty before that.
+    pub signature: Vec<u8>,
+}
+
+/// A block header could not be parsed.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    UnexpectedEnd,
+    NonCanonicalCompactSize,
+    OversizedField,
+    InvalidPastelId,
+    TrailingBytes,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnexpectedEnd => f.write_str("unexpected end of header data"),
+            Error::NonCanonicalCompactSize => f.write_str("non-canonical compact size"),
+            Error::OversizedField => f.write_str("field length exceeds maximum size"),
+            Error::InvalidPastelId => f.write_str("PastelID is not valid UTF-8"),
+            Error::TrailingBytes => f.write_str("unexpected data after header"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(_: std::io::Error) -> Self {
+        // Reads are only ever from an in-memory slice, so the only failure is
+        // running out of data.
+        Error::UnexpectedEnd
+    }
+}
+
+impl BlockHeader {
+    /// Parses the header at the start of `data`, such as a raw block, and
+    /// returns it along with the number of bytes it occupied.
+    pub fn read(data: &[u8]) -> Result<(Self, usize), Error> {
+        let mut csr = Cursor::new(data);
+
+        let version = csr.read_u32::<LittleEndian>()?;
+        let prev_hash = read_hash(&mut csr)?;
+        let merkle_root = read_hash(&mut csr)?;
+        let final_sapling_root = read_hash(&mut csr)?;
+        let time = csr.read_u32::<LittleEndian>()?;
+        let bits = csr.read_u32::<LittleEndian>()?;
+        let nonce = read_hash(&mut csr)?;
+        let solution = read_var_bytes(&mut csr)?;
+
+        let (pastel_id, signature) = if version >= SIGNED_BLOCK_VERSION {
+            let pastel_id =
+                String::from_utf8(read_var_bytes(&mut csr)?).map_err(|_| Error::InvalidPastelId)?;
+            (pastel_id, read_var_bytes(&mut csr)?)
+        } else {
+            (String::new(), Vec::new())
+        };
+
+        let header = BlockHeader {
+            version,
+            prev_hash,
+            merkle_root,
+            final_sapling_root,
+            time,
+            bits,
+            nonce,
+            solution,
+            pastel_id,
+            signature,
+        };
+        Ok((header, csr.position() as usize))
+    }
+
+    /// Parses `data`, which must contain exactly one serialized header.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let (header, len) = Self::read(data)?;
+        if len == data.len() {
+            Ok(header)
+        } else {
+            Err(Error::TrailingBytes)
+        }
+    }
+
+    /// Serializes the header in the node's wire format.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_prefix(&mut out);
+        out.extend_from_slice(&self.nonce);
+        write_var_bytes(&mut out, &self.solution);
+        self.write_signed_fields(&mut out);
+        out
+    }
+
+    /// The data Pastel hashes as the Equihash input: the fields before the
+    /// nonce, then the PastelID and signature, then the nonce.
+    pub fn equihash_input(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_prefix(&mut out);
+        self.write_signed_fields(&mut out);
+        out.extend_from_slice(&self.nonce);
+        out
+    }
+
+    /// The block hash: the double SHA-256 of the full serialized header, in
+    /// serialized byte order.
+    pub fn hash(&self) -> [u8; 32] {
+        Sha256::digest(Sha256::digest(self.serialize())).into()
+    }
+
+    /// Checks the header's Equihash solution against its own Equihash input
+    /// with the parameters `(n, k)`.
+    pub fn verify_solution(&self, n: u32, k: u32) -> Result<(), verify::Error> {
+        // The nonce is already the last part of the input.
+        verify::is_valid_solution(n, k, &self.equihash_input(), &[], &self.solution)
+    }
+
+    /// Writes the fixed-size fields from the version up to nBits.
+    fn write_prefix(&self, out: &mut Vec<u8>) {
+        out.write_u32::<LittleEndian>(self.version).unwrap();
+        out.extend_from_slice(&self.prev_hash);
+        out.extend_from_slice(&self.merkle_root);
+        out.extend_from_slice(&self.final_sapling_root);
+        out.write_u32::<LittleEndian>(self.time).unwrap();
+        out.write_u32::<LittleEndian>(self.bits).unwrap();
+    }
+
+    fn write_signed_fields(&self, out: &mut Vec<u8>) {
+        if self.version >= SIGNED_BLOCK_VERSION {
+            write_var_bytes(out, self.pastel_id.as_bytes());
+            write_var_bytes(out, &self.signature);
+        }
+    }
+}
+
+fn read_hash(csr: &mut Cursor<&[u8]>) -> Result<[u8; 32], Error> {
+    let mut hash = [0; 32];
+    csr.read_exact(&mut hash)?;
+    Ok(hash)
+}
+
+fn read_compact_size(csr: &mut Cursor<&[u8]>) -> Result<u64, Error> {
+    // Each prefix must be the shortest encoding of its value.
+    let size = match csr.read_u8()? {
+        0xfd => {
+            let size = u64::from(csr.read_u16::<LittleEndian>()?);
+            if size < 0xfd {
+                return Err(Error::NonCanonicalCompactSize);
+            }
+            size
+        }
+        0xfe => {
+            let size = u64::from(csr.read_u32::<LittleEndian>()?);
+            if size <= 0xffff {
+                return Err(Error::NonCanonicalCompactSize);
+            }
+            size
+        }
+        0xff => {
+            let size = csr.read_u64::<LittleEndian>()?;
+            if size <= 0xffff_ffff {
+                return Err(Error::NonCanonicalCompactSize);
+            }
+            size
+        }
+        size => u64::from(size),
+    };
+
+    if size > MAX_SIZE {
+        Err(Error::OversizedField)
+    } else {
+        Ok(size)
+    }
+}
+
+fn read_var_bytes(csr: &mut Cursor<&[u8]>) -> Result<Vec<u8>, Error> {
+    let len = read_compact_size(csr)? as usize;
+    let remaining = csr.get_ref().len() - csr.position() as usize;
+    if len > remaining {
+        return Err(Error::UnexpectedEnd);
+    }
+    let mut bytes = vec![0; len];
+    csr.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn write_compact_size(out: &mut Vec<u8>, size: u64) {
+    if size < 0xfd {
+        out.push(size as u8);
+    } else if size <= 0xffff {
+        out.push(0xfd);
+        out.write_u16::<LittleEndian>(size as u16).unwrap();
+    } else if size <= 0xffff_ffff {
+        out.push(0xfe);
+        out.write_u32::<LittleEndian>(size as u32).unwrap();
+    } else {
+        out.push(0xff);
+        out.write_u64::<LittleEndian>(size).unwrap();
+    }
+}
+
+fn write_var_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_compact_size(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_compact_size, BlockHeader, Error};
+    use crate::test_vectors::PASTEL_V5_BLOCK;
+    use crate::verify::{is_valid_solution, Kind};
+    use std::io::Cursor;
+
+    #[test]
+    fn pastel_v5_block() {
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (header, len) = BlockHeader::read(&block).unwrap();
+
+        assert_eq!(len, 1689);
+        assert_eq!(header.version, 5);
+        assert_eq!(header.solution.len(), 1344);
+        assert_eq!(
+            header.pastel_id,
+            "jXXwP91HjyZ2q5zFfHAQCeoDK5TvnwEYuJcJYxXsq9xeYgmULk3SR8Er2iymoTaQ4N9M2rcowFBJGXoZ6ye1gN"
+        );
+        assert_eq!(header.signature.len(), 114);
+        assert_eq!(
+            hex::encode(header.nonce),
+            "0600f2e5a3dc7d15ebd662139ebbae38ab99cfd65eeef76428f237f08e000000"
+        );
+
+        // Serialization is the exact inverse of parsing.
+        assert_eq!(header.serialize(), &block[..len]);
+        assert_eq!(BlockHeader::parse(&block[..len]).unwrap(), header);
+        assert_eq!(BlockHeader::parse(&block), Err(Error::TrailingBytes));
+    }
+
+    #[test]
+    fn pastel_v5_equihash_input() {
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (header, _) = BlockHeader::read(&block).unwrap();
+
+        let input = header.equihash_input();
+        assert_eq!(input.len(), 108 + 1 + 86 + 1 + 114 + 32);
+        is_valid_solution(200, 9, &input, &[], &header.solution).unwrap();
+    }
+
+    #[test]
+    fn pastel_v5_verify_solution() {
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (mut header, _) = BlockHeader::read(&block).unwrap();
+        header.verify_solution(200, 9).unwrap();
+
+        assert_eq!(
+            header.verify_solution(144, 5).unwrap_err().kind(),
+            Kind::InvalidParams
+        );
+
+        // The signed fields are part of the Equihash input.
+        header.signature[0] ^= 1;
+        header.verify_solution(200, 9).unwrap_err();
+    }
+
+    #[test]
+    fn v4_header_has_no_signed_fields() {
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (mut header, _) = BlockHeader::read(&block).unwrap();
+        header.version = 4;
+        header.pastel_id.clear();
+        header.signature.clear();
+
+        let serialized = header.serialize();
+        assert_eq!(serialized.len(), 140 + 3 + 1344);
+        assert_eq!(BlockHeader::parse(&serialized).unwrap(), header);
+    }
+
+    #[test]
+    fn truncated_header() {
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (_, len) = BlockHeader::read(&block).unwrap();
+        for end in [0, 4, 100, 140, 141, 143, 1000, len - 1] {
+            assert_eq!(
+                BlockHeader::read(&block[..end]).unwrap_err(),
+                Error::UnexpectedEnd
+            );
+        }
+    }
+
+    #[test]
+    fn compact_size() {
+        let check = |bytes: &[u8]| read_compact_size(&mut Cursor::new(bytes));
+
+        assert_eq!(check(&[0xfc]), Ok(0xfc));
+        assert_eq!(check(&[0xfd, 0xfd, 0x00]), Ok(0xfd));
+        assert_eq!(check(&[0xfd, 0x40, 0x05]), Ok(1344));
+        assert_eq!(check(&[0xfe, 0x00, 0x00, 0x00, 0x02]), Ok(0x0200_0000));
+
+        assert_eq!(
+            check(&[0xfd, 0xfc, 0x00]),
+            Err(Error::NonCanonicalCompactSize)
+        );
+        assert_eq!(
+            check(&[0xfe, 0xff, 0xff, 0x00, 0x00]),
+            Err(Error::NonCanonicalCompactSize)
+        );
+        assert_eq!(
+            check(&[0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::NonCanonicalCompactSize)
+        );
+        assert_eq!(
+            check(&[0xfe, 0x01, 0x00, 0x00, 0x02]),
+            Err(Error::OversizedField)
+        );
+        assert_eq!(check(&[0xfd, 0x00]), Err(Error::UnexpectedEnd));
+    }
+}
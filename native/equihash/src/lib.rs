@@ -0,0 +1,23 @@
+//! Equihash solution verification and solving, along with Pastel block header
+//! parsing and proof-of-work checks.
+//!
+//! Without the default `std` feature the crate is `no_std` and needs only
+//! `alloc`. Verification, solving and the proof-of-work checks are still
+//! available; `batch` and `header` are not.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod batch;
+pub mod context;
+#[cfg(feature = "std")]
+pub mod header;
+pub mod pow;
+pub mod solve;
+pub mod verify;
+
+#[cfg(any(test, feature = "test-vectors"))]
+#[doc(hidden)]
+pub mod test_vectors;
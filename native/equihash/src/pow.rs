@@ -0,0 +1,372 @@
+use core::cmp::Ordering;
+use core::fmt;
+
+/// The compact form of the easiest target allowed on mainnet.
+pub const POW_LIMIT_BITS: u32 = 0x2007_ffff;
+
+/// An unsigned 256-bit integer, as used for targets and chain work.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct U256([u64; 4]);
+
+impl U256 {
+    pub const ZERO: U256 = U256([0; 4]);
+    pub const ONE: U256 = U256([1, 0, 0, 0]);
+
+    /// Interprets `bytes` as a little-endian number, which is how block hashes
+    /// are compared against targets.
+    pub fn from_le_bytes(bytes: &[u8; 32]) -> Self {
+        let mut limbs = [0; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            *limb = u64::from_le_bytes(word);
+        }
+        U256(limbs)
+    }
+
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn to_be_bytes(self) -> [u8; 32] {
+        let mut bytes = self.to_le_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == U256::ZERO
+    }
+
+    pub fn checked_add(self, other: U256) -> Option<U256> {
+        let mut out = [0; 4];
+        let mut carry = false;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        if carry {
+            None
+        } else {
+            Some(U256(out))
+        }
+    }
+
+    fn wrapping_sub(self, other: U256) -> U256 {
+        let mut out = [0; 4];
+        let mut borrow = false;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (diff, b1) = self.0[i].overflowing_sub(other.0[i]);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+        U256(out)
+    }
+
+    fn not(self) -> U256 {
+        U256([!self.0[0], !self.0[1], !self.0[2], !self.0[3]])
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        (self.0[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    /// Shifts left by one bit, returning the bit shifted out of the top.
+    fn shl1(self) -> (U256, bool) {
+        let mut out = [0; 4];
+        let mut carry = 0;
+        for (i, limb) in out.iter_mut().enumerate() {
+            *limb = (self.0[i] << 1) | carry;
+            carry = self.0[i] >> 63;
+        }
+        (U256(out), carry == 1)
+    }
+
+    /// Long division. `divisor` must be non-zero.
+    fn div(self, divisor: U256) -> U256 {
+        let mut quotient = U256::ZERO;
+        let mut rem = U256::ZERO;
+        for i in (0..256).rev() {
+            let (shifted, overflow) = rem.shl1();
+            rem = shifted;
+            rem.0[0] |= self.bit(i) as u64;
+            // On overflow the true remainder is at least 2^256, so it is
+            // certainly not below the divisor and the wrapped subtraction
+            // gives the right result.
+            if overflow || rem >= divisor {
+                rem = rem.wrapping_sub(divisor);
+                quotient.0[i / 64] |= 1 << (i % 64);
+            }
+        }
+        quotient
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for U256 {
+    /// Formats as big-endian hex, the way the node displays hashes and chain
+    /// work.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.to_be_bytes()))
+    }
+}
+
+/// A block failed its proof-of-work check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// nBits has its sign bit set.
+    NegativeTarget,
+    /// nBits encodes a target that does not fit in 256 bits.
+    TargetOverflow,
+    ZeroTarget,
+    /// nBits is easier than the network's proof-of-work limit.
+    TargetAboveLimit,
+    /// The block hash does not meet the target.
+    HashAboveTarget,
+}
+
+impl Error {
+    /// A stable identifier for the failure, such as `HASH_ABOVE_TARGET`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::NegativeTarget => "NEGATIVE_TARGET",
+            Error::TargetOverflow => "TARGET_OVERFLOW",
+            Error::ZeroTarget => "ZERO_TARGET",
+            Error::TargetAboveLimit => "TARGET_ABOVE_LIMIT",
+            Error::HashAboveTarget => "HASH_ABOVE_TARGET",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NegativeTarget => f.write_str("nBits encodes a negative target"),
+            Error::TargetOverflow => f.write_str("nBits encodes a target wider than 256 bits"),
+            Error::ZeroTarget => f.write_str("nBits encodes a zero target"),
+            Error::TargetAboveLimit => f.write_str("target is above the proof-of-work limit"),
+            Error::HashAboveTarget => f.write_str("block hash does not meet the target"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Decodes the compact nBits form of a target.
+pub fn target_from_compact(bits: u32) -> Result<U256, Error> {
+    let size = (bits >> 24) as usize;
+    let mut word = bits & 0x007f_ffff;
+    if size <= 3 {
+        word >>= 8 * (3 - size);
+    }
+
+    if word != 0 {
+        if bits & 0x0080_0000 != 0 {
+            return Err(Error::NegativeTarget);
+        }
+        if size > 34 || (word > 0xff && size > 33) || (word > 0xffff && size > 32) {
+            return Err(Error::TargetOverflow);
+        }
+    }
+
+    let mut bytes = [0; 32];
+    if size <= 3 {
+        bytes[..4].copy_from_slice(&word.to_le_bytes());
+    } else {
+        // The mantissa is the top three bytes of a `size`-byte number.
+        for (i, b) in word.to_le_bytes()[..3].iter().enumerate() {
+            if let Some(byte) = bytes.get_mut(size - 3 + i) {
+                *byte = *b;
+            }
+        }
+    }
+    Ok(U256::from_le_bytes(&bytes))
+}
+
+/// Decodes nBits and checks it against the network's limit, as the node does
+/// before comparing a block hash.
+pub fn checked_target(bits: u32, pow_limit_bits: u32) -> Result<U256, Error> {
+    let target = target_from_compact(bits)?;
+    if target.is_zero() {
+        return Err(Error::ZeroTarget);
+    }
+    if target > target_from_compact(pow_limit_bits)? {
+        return Err(Error::TargetAboveLimit);
+    }
+    Ok(target)
+}
+
+/// Checks that `hash`, in its serialized byte order, meets the target encoded
+/// in `bits`.
+pub fn check_proof_of_work(hash: &[u8; 32], bits: u32, pow_limit_bits: u32) -> Result<(), Error> {
+    let target = checked_target(bits, pow_limit_bits)?;
+    if U256::from_le_bytes(hash) > target {
+        Err(Error::HashAboveTarget)
+    } else {
+        Ok(())
+    }
+}
+
+/// The expected number of hashes needed to meet `target`, which is what a
+/// block adds to the chain work. A zero target has no valid hashes and
+/// counts as no work, as in the node.
+pub fn work(target: U256) -> U256 {
+    if target.is_zero() {
+        return U256::ZERO;
+    }
+    // 2^256 itself does not fit in 256 bits, but 2^256 / (target + 1) equals
+    // ~target / (target + 1) + 1.
+    match target.checked_add(U256::ONE) {
+        Some(divisor) => target.not().div(divisor).checked_add(U256::ONE).unwrap(),
+        None => U256::ONE,
+    }
+}
+
+/// The difficulty of `bits` relative to the proof-of-work limit, computed the
+/// same way as the node's `getdifficulty`.
+pub fn difficulty(bits: u32, pow_limit_bits: u32) -> f64 {
+    let mut shift = (bits >> 24) & 0xff;
+    let limit_shift = (pow_limit_bits >> 24) & 0xff;
+    let mut diff = f64::from(pow_limit_bits & 0x00ff_ffff) / f64::from(bits & 0x00ff_ffff);
+
+    while shift < limit_shift {
+        diff *= 256.0;
+        shift += 1;
+    }
+    while shift > limit_shift {
+        diff /= 256.0;
+        shift -= 1;
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        checked_target, difficulty, target_from_compact, work, Error, POW_LIMIT_BITS, U256,
+    };
+
+    fn from_hex(s: &str) -> U256 {
+        let mut bytes = [0; 32];
+        let decoded = hex::decode(s).unwrap();
+        bytes[32 - decoded.len()..].copy_from_slice(&decoded);
+        bytes.reverse();
+        U256::from_le_bytes(&bytes)
+    }
+
+    #[test]
+    fn compact_targets() {
+        // Cases from the node's arith_uint256 tests.
+        assert_eq!(target_from_compact(0), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0012_3456), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0100_3456), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0200_0056), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0300_0000), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0400_0000), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0092_3456), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0180_3456), Ok(U256::ZERO));
+        assert_eq!(target_from_compact(0x0112_3456), Ok(from_hex("12")));
+        assert_eq!(target_from_compact(0x0212_3456), Ok(from_hex("1234")));
+        assert_eq!(target_from_compact(0x0312_3456), Ok(from_hex("123456")));
+        assert_eq!(target_from_compact(0x0412_3456), Ok(from_hex("12345600")));
+        assert_eq!(target_from_compact(0x0500_9234), Ok(from_hex("92340000")));
+        assert_eq!(
+            target_from_compact(0x2012_3456),
+            Ok(from_hex(
+                "1234560000000000000000000000000000000000000000000000000000000000"
+            ))
+        );
+        assert_eq!(
+            target_from_compact(POW_LIMIT_BITS),
+            Ok(from_hex(
+                "07ffff0000000000000000000000000000000000000000000000000000000000"
+            ))
+        );
+
+        assert_eq!(target_from_compact(0x0192_3456), Err(Error::NegativeTarget));
+        assert_eq!(target_from_compact(0x0492_3456), Err(Error::NegativeTarget));
+        assert_eq!(target_from_compact(0xff12_3456), Err(Error::TargetOverflow));
+        assert_eq!(target_from_compact(0x2101_0000), Err(Error::TargetOverflow));
+    }
+
+    #[test]
+    fn target_limits() {
+        assert_eq!(checked_target(0, POW_LIMIT_BITS), Err(Error::ZeroTarget));
+        assert_eq!(
+            checked_target(0x2008_0000, POW_LIMIT_BITS),
+            Err(Error::TargetAboveLimit)
+        );
+        assert!(checked_target(POW_LIMIT_BITS, POW_LIMIT_BITS).is_ok());
+        assert!(checked_target(0x2008_0000, 0x2100_ff00).is_ok());
+    }
+
+    #[test]
+    fn block_work() {
+        assert_eq!(work(U256::ZERO), U256::ZERO);
+        assert_eq!(
+            work(from_hex(
+                "ffff0000000000000000000000000000000000000000000000000000"
+            )),
+            from_hex("0100010001")
+        );
+        assert_eq!(
+            work(from_hex(
+                "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+            )),
+            from_hex("02")
+        );
+        assert_eq!(
+            work(target_from_compact(POW_LIMIT_BITS).unwrap()),
+            from_hex("20")
+        );
+    }
+
+    #[test]
+    fn difficulties() {
+        assert_eq!(difficulty(POW_LIMIT_BITS, POW_LIMIT_BITS), 1.0);
+        assert_eq!(difficulty(0x1f07_ffff, POW_LIMIT_BITS), 256.0);
+        assert_eq!(
+            difficulty(0x2003_ffff, POW_LIMIT_BITS),
+            0x7ffff as f64 / 0x3ffff as f64
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn pastel_v5_proof_of_work() {
+        use super::check_proof_of_work;
+        use crate::header::BlockHeader;
+        use crate::test_vectors::PASTEL_V5_BLOCK;
+
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (header, _) = BlockHeader::read(&block).unwrap();
+        let hash = header.hash();
+        check_proof_of_work(&hash, header.bits, POW_LIMIT_BITS).unwrap();
+
+        let mut easy = hash;
+        easy[31] = 0xff;
+        assert_eq!(
+            check_proof_of_work(&easy, header.bits, POW_LIMIT_BITS),
+            Err(Error::HashAboveTarget)
+        );
+    }
+}
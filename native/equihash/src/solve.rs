@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+
+use crate::verify::{
+    distinct_indices, generate_hash, has_collision, initialise_state, Node, Params,
+};
+
+/// Finds every solution for `(input, nonce)` with the parameters `p`.
+///
+/// This is the basic form of Wagner's algorithm: it keeps the full index list
+/// of every partial solution in memory, so it is meant for small parameters
+/// such as regtest's 48,5 or the 96,5 test vectors rather than 200,9. The
+/// solutions are returned as index lists in the order that
+/// [`is_valid_solution`](crate::verify::is_valid_solution) accepts, sorted and
+/// without duplicates.
+pub fn solve(p: Params, input: &[u8], nonce: &[u8]) -> Vec<Vec<u32>> {
+    let mut state = initialise_state(&p);
+    state.update(input);
+    state.update(nonce);
+
+    // Generate the 2^(collision bit length + 1) leaves, sharing each Blake2b
+    // output between the indices it covers.
+    let init_size = 1usize << (p.collision_bit_length() + 1);
+    let mut rows = Vec::with_capacity(init_size);
+    let mut g = 0;
+    while rows.len() < init_size {
+        let hash = generate_hash(&state, g);
+        for i in 0..p.indices_per_hash_output() {
+            if rows.len() == init_size {
+                break;
+            }
+            let index = g * p.indices_per_hash_output() + i;
+            rows.push(Node::from_hash_output(&p, hash.as_bytes(), index));
+        }
+        g += 1;
+    }
+
+    // Each of the first k - 1 rounds pairs rows that collide on the next
+    // collision_byte_length bytes, and drops those bytes from the result.
+    let trim = p.collision_byte_length();
+    for _ in 1..p.k {
+        rows.sort_unstable_by(|a, b| a.hash.cmp(&b.hash));
+        let mut next = Vec::new();
+        for_each_collision(&rows, trim, |a, b| {
+            if distinct_indices(a, b) {
+                next.push(Node::from_children_ref(a, b, trim));
+            }
+        });
+        rows = next;
+    }
+
+    // In the final round the remaining 2 * collision_byte_length bytes must
+    // all collide, so that the root hash is zero.
+    rows.sort_unstable_by(|a, b| a.hash.cmp(&b.hash));
+    let mut solutions = Vec::new();
+    for_each_collision(&rows, 2 * trim, |a, b| {
+        if distinct_indices(a, b) {
+            solutions.push(Node::from_children_ref(a, b, trim).indices);
+        }
+    });
+
+    solutions.sort_unstable();
+    solutions.dedup();
+    solutions
+}
+
+/// Calls `f` on every pair of rows that collide on their first `len` bytes.
+/// `rows` must be sorted by hash.
+fn for_each_collision<F>(rows: &[Node], len: usize, mut f: F)
+where
+    F: FnMut(&Node, &Node),
+{
+    let mut i = 0;
+    while i < rows.len() {
+        let mut j = i + 1;
+        while j < rows.len() && has_collision(&rows[i], &rows[j], len) {
+            j += 1;
+        }
+        for l in i..j {
+            for m in (l + 1)..j {
+                f(&rows[l], &rows[m]);
+            }
+        }
+        i = j;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve;
+    use crate::test_vectors::VALID_TEST_VECTORS;
+    use crate::verify::{
+        indices_to_minimal, is_valid_solution, is_valid_solution_with_params, Params,
+        BGOLD_PERSONALIZATION,
+    };
+
+    #[test]
+    fn finds_valid_test_vectors() {
+        // Solving is slow in debug builds, so only check the vectors with many
+        // solutions and the one with a 3+-way collision in the final round.
+        for tv in VALID_TEST_VECTORS.iter().filter(|tv| tv.params.n == 96) {
+            if tv.solutions.len() < 5 && !tv.input.starts_with(b"Test case with 3+-way") {
+                continue;
+            }
+            let solutions = solve(tv.params, tv.input, &tv.nonce);
+            assert_eq!(solutions, tv.solutions);
+        }
+    }
+
+    #[test]
+    fn solutions_depend_on_personalization() {
+        let p = Params::new(96, 5)
+            .unwrap()
+            .with_personalization(BGOLD_PERSONALIZATION);
+        let input = b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.";
+
+        // Not every nonce has a solution, so try a few.
+        let (nonce, solutions) = (0..8u8)
+            .map(|i| ([i; 32], solve(p, input, &[i; 32])))
+            .find(|(_, solutions)| !solutions.is_empty())
+            .unwrap();
+        for indices in solutions {
+            let soln = indices_to_minimal(p, &indices).unwrap();
+            is_valid_solution_with_params(p, input, &nonce, &soln).unwrap();
+            is_valid_solution(96, 5, input, &nonce, &soln).unwrap_err();
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Known-good and known-bad solutions, plus a real Pastel block. Public only
+//! so that other crates in the workspace can run them through their own
+//! bindings.
+
+mod blocks;
+mod invalid;
+mod valid;
+
+pub use blocks::PASTEL_V5_BLOCK;
+pub use invalid::{TestVector as InvalidTestVector, INVALID_TEST_VECTORS};
+pub use valid::{TestVector as ValidTestVector, VALID_TEST_VECTORS};
@@ -1,17 +1,14 @@
-#[cfg(test)]
 use crate::verify::{Kind, Params};
 
-#[cfg(test)]
-pub(crate) struct TestVector {
-    pub(crate) params: Params,
-    pub(crate) input: &'static [u8],
-    pub(crate) nonce: [u8; 32],
-    pub(crate) solution: &'static [u32],
-    pub(crate) error: Kind,
+pub struct TestVector {
+    pub params: Params,
+    pub input: &'static [u8],
+    pub nonce: [u8; 32],
+    pub solution: &'static [u32],
+    pub error: Kind,
 }
 
-#[cfg(test)]
-pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
+pub const INVALID_TEST_VECTORS: &[TestVector] = &[
     // Original valid solution: [
     //     2261, 15185, 36112, 104243, 23779, 118390, 118332, 130041, 32642, 69878, 76925, 80080,
     //     45858, 116805, 92842, 111026, 15972, 115059, 85191, 90330, 68190, 122819, 81830, 91132,
@@ -20,7 +17,7 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
 
     // Change one index
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -33,9 +30,11 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
         ],
         error: Kind::Collision,
     },
-    // Swap two arbitrary indices
+    // Swap two arbitrary indices. Zcash reports a collision, which its verifier
+    // finds before reaching the misordered merge, but ordering is checked
+    // before any hashing here.
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -46,11 +45,11 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
             2261, 116805, 92842, 111026, 15972, 115059, 85191, 90330, 68190, 122819, 81830, 91132,
             23460, 49807, 52426, 80391, 69567, 114474, 104973, 122568,
         ],
-        error: Kind::Collision,
+        error: Kind::OutOfOrder,
     },
     // Reverse the first pair of indices
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -65,7 +64,7 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
     },
     // Swap the first and second pairs of indices
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -80,7 +79,7 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
     },
     // Swap the second-to-last and last pairs of indices
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -95,7 +94,7 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
     },
     // Swap the first half and second half
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -110,7 +109,7 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
     },
     // Sort the indices
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -125,7 +124,7 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
     },
     // Duplicate indices
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -140,7 +139,7 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
     },
     // Duplicate first half
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -153,4 +152,4 @@ pub(crate) const INVALID_TEST_VECTORS: &[TestVector] = &[
         ],
         error: Kind::DuplicateIdxs,
     },
-];
\ No newline at end of file
+];
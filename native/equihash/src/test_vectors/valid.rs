@@ -1,18 +1,15 @@
-#[cfg(test)]
 use crate::verify::Params;
 
-#[cfg(test)]
-pub(crate) struct TestVector {
-    pub(crate) params: Params,
-    pub(crate) input: &'static [u8],
-    pub(crate) nonce: [u8; 32],
-    pub(crate) solutions: &'static [&'static [u32]],
+pub struct TestVector {
+    pub params: Params,
+    pub input: &'static [u8],
+    pub nonce: [u8; 32],
+    pub solutions: &'static [&'static [u32]],
 }
 
-#[cfg(test)]
-pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
+pub const VALID_TEST_VECTORS: &[TestVector] = &[
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"block header",
         nonce: [0; 32],
         solutions: &[
@@ -44,7 +41,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"block header",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -57,7 +54,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ]],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"block header",
         nonce: [
             2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -70,7 +67,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ]],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"block header",
         nonce: [
             10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -90,7 +87,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"block header",
         nonce: [
             11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -103,7 +100,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ]],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [0; 32],
         solutions: &[
@@ -120,7 +117,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -140,7 +137,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -153,7 +150,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ]],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -183,7 +180,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.",
         nonce: [
             11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -196,7 +193,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ]],
     },
     TestVector {
-        params: Params { n: 96, k: 5 },
+        params: Params::new_unchecked(96, 5),
         input: b"Test case with 3+-way collision in the final round.",
         nonce: [
             0xf0, 0x07, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -236,7 +233,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 200, k: 9 },
+        params: Params::new_unchecked(200, 9),
         input: b"block header",
         nonce: [0; 32],
         solutions: &[&[
@@ -295,7 +292,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ]],
     },
     TestVector {
-        params: Params { n: 200, k: 9 },
+        params: Params::new_unchecked(200, 9),
         input: b"block header",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -418,7 +415,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 200, k: 9 },
+        params: Params::new_unchecked(200, 9),
         input: b"block header",
         nonce: [
             2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -540,7 +537,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 200, k: 9 },
+        params: Params::new_unchecked(200, 9),
         input: b"block header",
         nonce: [
             10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -664,7 +661,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 200, k: 9 },
+        params: Params::new_unchecked(200, 9),
         input: b"block header",
         nonce: [
             11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -726,7 +723,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ]],
     },
     TestVector {
-        params: Params { n: 144, k: 5 },
+        params: Params::new_unchecked(144, 5),
         input: b"block header",
         nonce: [0; 32],
         solutions: &[
@@ -751,7 +748,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 144, k: 5 },
+        params: Params::new_unchecked(144, 5),
         input: b"block header",
         nonce: [
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -791,7 +788,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 144, k: 5 },
+        params: Params::new_unchecked(144, 5),
         input: b"block header",
         nonce: [
             2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -819,7 +816,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         ],
     },
     TestVector {
-        params: Params { n: 144, k: 5 },
+        params: Params::new_unchecked(144, 5),
         input: b"block header",
         nonce: [
             10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -828,7 +825,7 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
         solutions: &[],
     },
     TestVector {
-        params: Params { n: 144, k: 5 },
+        params: Params::new_unchecked(144, 5),
         input: b"block header",
         nonce: [
             11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -841,4 +838,4 @@ pub(crate) const VALID_TEST_VECTORS: &[TestVector] = &[
             26893604, 13111251, 30773720, 14408826, 26047501,
         ]],
     },
-];
\ No newline at end of file
+];
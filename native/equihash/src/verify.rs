@@ -0,0 +1,1520 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use blake2b_simd::{Hash as Blake2bHash, Params as Blake2bParams, State as Blake2bState};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use core::convert::TryInto;
+use core::fmt;
+use core::mem::size_of;
+
+/// The Blake2b personalization prefix used by Zcash and its descendants,
+/// including Pastel.
+pub const ZCASH_PERSONALIZATION: [u8; 8] = *b"ZcashPoW";
+/// Bitcoin Gold's prefix, used with 144,5.
+pub const BGOLD_PERSONALIZATION: [u8; 8] = *b"BgoldPoW";
+/// BitcoinZ's prefix, used with 144,5.
+pub const BITCOINZ_PERSONALIZATION: [u8; 8] = *b"BitcoinZ";
+
+/// The personalization presets by name.
+pub const PERSONALIZATION_PRESETS: &[(&str, [u8; 8])] = &[
+    ("zcash", ZCASH_PERSONALIZATION),
+    ("bgold", BGOLD_PERSONALIZATION),
+    ("bitcoinz", BITCOINZ_PERSONALIZATION),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    pub(crate) n: u32,
+    pub(crate) k: u32,
+    /// Prefix of the Blake2b personalization; `n` and `k` make up the rest.
+    pub(crate) personalization: [u8; 8],
+}
+
+#[derive(Clone)]
+pub(crate) struct Node {
+    pub(crate) hash: Vec<u8>,
+    pub(crate) indices: Vec<u32>,
+}
+
+impl Params {
+    pub fn new(n: u32, k: u32) -> Result<Self, Error> {
+        // We place the following requirements on the parameters:
+        // - n is a multiple of 8, so the hash output has an exact byte length.
+        // - k >= 3 so the encoded solutions have an exact byte length.
+        // - k < n, so the collision bit length is at least 1.
+        // - n is a multiple of k + 1, so we have an integer collision bit length.
+        // - n <= 512, so at least one hash fits in a Blake2b output.
+        // - The collision bit length is between 8 and 24, the widths that
+        //   expand_array can handle for both hashes and (one bit wider) indices.
+        // - The minimal solution's length in bits fits in a usize.
+        if !(n.is_multiple_of(8) && (k >= 3) && (k < n) && n.is_multiple_of(k + 1) && n <= 512) {
+            return Err(Error::from(Kind::InvalidParams));
+        }
+        let c_bit_len = (n / (k + 1)) as usize;
+        let soln_bits = 1usize
+            .checked_shl(k)
+            .and_then(|indices| indices.checked_mul(c_bit_len + 1));
+        if (8..=24).contains(&c_bit_len) && soln_bits.is_some() {
+            Ok(Params {
+                n,
+                k,
+                personalization: ZCASH_PERSONALIZATION,
+            })
+        } else {
+            Err(Error::from(Kind::InvalidParams))
+        }
+    }
+    /// Builds Zcash parameters without checking them, for use in constants.
+    #[cfg(any(test, feature = "test-vectors"))]
+    pub(crate) const fn new_unchecked(n: u32, k: u32) -> Self {
+        Params {
+            n,
+            k,
+            personalization: ZCASH_PERSONALIZATION,
+        }
+    }
+    pub fn n(&self) -> u32 {
+        self.n
+    }
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+    /// Replaces the default Zcash personalization prefix.
+    pub fn with_personalization(mut self, personalization: [u8; 8]) -> Self {
+        self.personalization = personalization;
+        self
+    }
+    pub(crate) fn indices_per_hash_output(&self) -> u32 {
+        512 / self.n
+    }
+    fn hash_output(&self) -> u8 {
+        (self.indices_per_hash_output() * self.n / 8) as u8
+    }
+    pub(crate) fn collision_bit_length(&self) -> usize {
+        (self.n / (self.k + 1)) as usize
+    }
+    pub(crate) fn collision_byte_length(&self) -> usize {
+        self.collision_bit_length().div_ceil(8)
+    }
+    /// The number of indices in a solution, 2^k.
+    pub fn solution_indices(&self) -> usize {
+        1 << self.k
+    }
+    /// The number of bits each index takes in the minimal encoding.
+    pub fn index_bit_length(&self) -> usize {
+        self.collision_bit_length() + 1
+    }
+    /// The length in bytes of a solution in the minimal encoding.
+    pub fn solution_len(&self) -> usize {
+        // Division is exact because k >= 3.
+        self.solution_indices() * self.index_bit_length() / 8
+    }
+    /// The length of a leaf's expanded hash, `k + 1` collision-sized chunks.
+    fn hash_length(&self) -> usize {
+        ((self.k as usize) + 1) * self.collision_byte_length()
+    }
+}
+
+impl Node {
+    #[cfg(test)]
+    fn new(p: &Params, state: &Blake2bState, i: u32) -> Self {
+        let hash = generate_hash(state, i / p.indices_per_hash_output());
+        Node::from_hash_output(p, hash.as_bytes(), i)
+    }
+
+    /// Builds the leaf for index `i` from the Blake2b output that contains it.
+    pub(crate) fn from_hash_output(p: &Params, hash: &[u8], i: u32) -> Self {
+        let start = ((i % p.indices_per_hash_output()) * p.n / 8) as usize;
+        let end = start + (p.n as usize) / 8;
+        Node {
+            hash: expand_array(&hash[start..end], p.collision_bit_length(), 0),
+            indices: vec![i],
+        }
+    }
+
+    // Clippy incorrectly interprets the first argument as `self`.
+    #[cfg(test)]
+    #[allow(clippy::wrong_self_convention)]
+    fn from_children(a: Node, b: Node, trim: usize) -> Self {
+        let hash: Vec<_> = a
+            .hash
+            .iter()
+            .zip(b.hash.iter())
+            .skip(trim)
+            .map(|(a, b)| a ^ b)
+            .collect();
+        let indices = if a.indices_before(&b) {
+            let mut indices = a.indices;
+            indices.extend(b.indices.iter());
+            indices
+        } else {
+            let mut indices = b.indices;
+            indices.extend(a.indices.iter());
+            indices
+        };
+        Node { hash, indices }
+    }
+
+    pub(crate) fn from_children_ref(a: &Node, b: &Node, trim: usize) -> Self {
+        let len = a.hash.len().min(b.hash.len());
+        let mut hash = a.hash[trim.min(len)..len].to_vec();
+        xor_into(&mut hash, &b.hash[trim.min(len)..len]);
+        let mut indices = Vec::with_capacity(a.indices.len() + b.indices.len());
+        if a.indices_before(b) {
+            indices.extend(a.indices.iter());
+            indices.extend(b.indices.iter());
+        } else {
+            indices.extend(b.indices.iter());
+            indices.extend(a.indices.iter());
+        }
+        Node { hash, indices }
+    }
+
+    fn indices_before(&self, other: &Node) -> bool {
+        // Indices are serialized in big-endian so that integer
+        // comparison is equivalent to array comparison
+        self.indices[0] < other.indices[0]
+    }
+
+    #[cfg(test)]
+    fn is_zero(&self, len: usize) -> bool {
+        self.hash.iter().take(len).all(|v| *v == 0)
+    }
+}
+
+/// An Equihash solution failed to verify.
+#[derive(Debug)]
+pub struct Error {
+    kind: Kind,
+    location: Option<Location>,
+}
+
+impl Error {
+    fn at(kind: Kind, location: Location) -> Self {
+        Error {
+            kind,
+            location: Some(location),
+        }
+    }
+
+    /// The reason the solution was rejected.
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// The pair of subtrees that failed to validate, if the failure happened
+    /// while merging the solution tree.
+    pub fn location(&self) -> Option<Location> {
+        self.location
+    }
+}
+
+impl From<Kind> for Error {
+    fn from(kind: Kind) -> Self {
+        Error {
+            kind,
+            location: None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid solution: {}", self.kind)?;
+        if let Some(location) = self.location {
+            write!(f, " ({})", location)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    InvalidParams,
+    Collision,
+    OutOfOrder,
+    DuplicateIdxs,
+    NonZeroRootHash,
+}
+
+impl Kind {
+    /// A stable identifier for the kind, such as `COLLISION`, for reporting to
+    /// callers that match on it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Kind::InvalidParams => "INVALID_PARAMS",
+            Kind::Collision => "COLLISION",
+            Kind::OutOfOrder => "OUT_OF_ORDER",
+            Kind::DuplicateIdxs => "DUPLICATE_IDXS",
+            Kind::NonZeroRootHash => "NON_ZERO_ROOT_HASH",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::InvalidParams => f.write_str("invalid parameters"),
+            Kind::Collision => f.write_str("invalid collision length between StepRows"),
+            Kind::OutOfOrder => f.write_str("Index tree incorrectly ordered"),
+            Kind::DuplicateIdxs => f.write_str("duplicate indices"),
+            Kind::NonZeroRootHash => f.write_str("root hash of tree is non-zero"),
+        }
+    }
+}
+
+/// The position in the solution tree of two sibling subtrees that failed to
+/// validate against each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// The tree level being merged, from 1 (pairs of leaves) up to k (the two
+    /// halves of the full solution).
+    pub level: u32,
+    /// Position in the index list of the first index of the left subtree.
+    pub left: usize,
+    /// Position in the index list of the first index of the right subtree.
+    pub right: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "level {}, subtrees at positions {} and {}",
+            self.level, self.left, self.right
+        )
+    }
+}
+
+/// Returns the Blake2b state for `p` before any input has been absorbed, so
+/// that it can be shared between verifications with the same parameters.
+pub(crate) fn initialise_state(p: &Params) -> Blake2bState {
+    let mut personalization = [0u8; 16];
+    personalization[..8].copy_from_slice(&p.personalization);
+    LittleEndian::write_u32(&mut personalization[8..12], p.n);
+    LittleEndian::write_u32(&mut personalization[12..], p.k);
+
+    Blake2bParams::new()
+        .hash_length(p.hash_output() as usize)
+        .personal(&personalization)
+        .to_state()
+}
+
+pub(crate) fn generate_hash(base_state: &Blake2bState, i: u32) -> Blake2bHash {
+    let mut lei = [0u8; 4];
+    LittleEndian::write_u32(&mut lei, i);
+
+    let mut state = base_state.clone();
+    state.update(&lei);
+    state.finalize()
+}
+
+fn expanded_len(vin_len: usize, bit_len: usize, byte_pad: usize) -> usize {
+    8 * (bit_len.div_ceil(8) + byte_pad) * vin_len / bit_len
+}
+
+fn expand_array(vin: &[u8], bit_len: usize, byte_pad: usize) -> Vec<u8> {
+    let mut vout = vec![0; expanded_len(vin.len(), bit_len, byte_pad)];
+    expand_array_into(vin, bit_len, byte_pad, &mut vout);
+    vout
+}
+
+/// Expands `vin` into `vout`, which must be exactly the expanded length.
+fn expand_array_into(vin: &[u8], bit_len: usize, byte_pad: usize, vout: &mut [u8]) {
+    assert!(bit_len >= 8);
+    assert!(u32::BITS as usize >= 7 + bit_len);
+
+    let out_width = bit_len.div_ceil(8) + byte_pad;
+    assert_eq!(vout.len(), expanded_len(vin.len(), bit_len, byte_pad));
+
+    // Shortcut for parameters where expansion is a no-op
+    if vout.len() == vin.len() {
+        vout.copy_from_slice(vin);
+        return;
+    }
+
+    let bit_len_mask: u32 = (1 << bit_len) - 1;
+
+    // The acc_bits least-significant bits of acc_value represent a bit sequence
+    // in big-endian order.
+    let mut acc_bits = 0;
+    let mut acc_value: u32 = 0;
+
+    let mut j = 0;
+    for b in vin {
+        acc_value = (acc_value << 8) | u32::from(*b);
+        acc_bits += 8;
+
+        // When we have bit_len or more bits in the accumulator, write the next
+        // output element.
+        if acc_bits >= bit_len {
+            acc_bits -= bit_len;
+            for x in byte_pad..out_width {
+                vout[j + x] = ((
+                    // Big-endian
+                    acc_value >> (acc_bits + (8 * (out_width - x - 1)))
+                ) & (
+                    // Apply bit_len_mask across byte boundaries
+                    (bit_len_mask >> (8 * (out_width - x - 1))) & 0xFF
+                )) as u8;
+            }
+            j += out_width;
+        }
+    }
+}
+
+fn compress_array(vin: &[u8], bit_len: usize, byte_pad: usize) -> Vec<u8> {
+    assert!(bit_len >= 8);
+    assert!(u32::BITS as usize >= 7 + bit_len);
+
+    let in_width = bit_len.div_ceil(8) + byte_pad;
+    let out_len = bit_len * vin.len() / (8 * in_width);
+
+    let mut vout: Vec<u8> = vec![0; out_len];
+    let bit_len_mask: u32 = (1 << bit_len) - 1;
+
+    // The acc_bits least-significant bits of acc_value represent a bit sequence
+    // in big-endian order.
+    let mut acc_bits = 0;
+    let mut acc_value: u32 = 0;
+
+    let mut j = 0;
+    for out in vout.iter_mut() {
+        // When we have fewer than 8 bits left in the accumulator, read the next
+        // input element.
+        if acc_bits < 8 {
+            acc_value <<= bit_len;
+            for x in byte_pad..in_width {
+                acc_value |= (
+                    // Apply bit_len_mask across byte boundaries
+                    u32::from(vin[j + x]) & ((bit_len_mask >> (8 * (in_width - x - 1))) & 0xFF)
+                ) << (
+                    // Big-endian
+                    8 * (in_width - x - 1)
+                );
+            }
+            j += in_width;
+            acc_bits += bit_len;
+        }
+
+        acc_bits -= 8;
+        *out = (acc_value >> acc_bits) as u8;
+    }
+
+    vout
+}
+
+pub fn indices_from_minimal(p: Params, minimal: &[u8]) -> Result<Vec<u32>, Error> {
+    let c_bit_len = p.collision_bit_length();
+    if minimal.len() != p.solution_len() {
+        return Err(Error::from(Kind::InvalidParams));
+    }
+
+    assert!((c_bit_len + 1).div_ceil(8) <= size_of::<u32>());
+    let byte_pad = size_of::<u32>() - (c_bit_len + 1).div_ceil(8);
+
+    let expanded = expand_array(minimal, c_bit_len + 1, byte_pad);
+
+    // Big-endian so that lexicographic array comparison is equivalent to integer
+    // comparison
+    Ok(expanded
+        .chunks_exact(size_of::<u32>())
+        .map(BigEndian::read_u32)
+        .collect())
+}
+
+/// Encodes a solution's `indices` into the minimal byte form, the inverse of
+/// [`indices_from_minimal`].
+pub fn indices_to_minimal(p: Params, indices: &[u32]) -> Result<Vec<u8>, Error> {
+    let c_bit_len = p.collision_bit_length();
+    if indices.len() != 1 << p.k {
+        return Err(Error::from(Kind::InvalidParams));
+    }
+    // Each index must fit in c_bit_len + 1 bits.
+    if indices
+        .iter()
+        .any(|i| u64::from(*i) >> (c_bit_len + 1) != 0)
+    {
+        return Err(Error::from(Kind::InvalidParams));
+    }
+
+    assert!((c_bit_len + 1).div_ceil(8) <= size_of::<u32>());
+    let byte_pad = size_of::<u32>() - (c_bit_len + 1).div_ceil(8);
+
+    let mut array = vec![0; core::mem::size_of_val(indices)];
+    BigEndian::write_u32_into(indices, &mut array);
+
+    Ok(compress_array(&array, c_bit_len + 1, byte_pad))
+}
+
+/// The word size for the hash operations below. Working on whole words lets
+/// the compiler vectorize the loops, without any platform-specific code.
+const WORD: usize = size_of::<u64>();
+
+fn load(bytes: &[u8]) -> u64 {
+    u64::from_ne_bytes(bytes.try_into().unwrap())
+}
+
+/// XORs `src` into `dst`, which must be the same length.
+pub(crate) fn xor_into(dst: &mut [u8], src: &[u8]) {
+    assert_eq!(dst.len(), src.len());
+    let mut dst = dst.chunks_exact_mut(WORD);
+    let mut src = src.chunks_exact(WORD);
+    for (d, s) in (&mut dst).zip(&mut src) {
+        d.copy_from_slice(&(load(d) ^ load(s)).to_ne_bytes());
+    }
+    for (d, s) in dst.into_remainder().iter_mut().zip(src.remainder()) {
+        *d ^= s;
+    }
+}
+
+/// Whether `a` and `b`, which must be the same length, are equal.
+fn words_eq(a: &[u8], b: &[u8]) -> bool {
+    assert_eq!(a.len(), b.len());
+    let mut a = a.chunks_exact(WORD);
+    let mut b = b.chunks_exact(WORD);
+    (&mut a).zip(&mut b).all(|(a, b)| load(a) == load(b)) && a.remainder() == b.remainder()
+}
+
+fn is_zero(bytes: &[u8]) -> bool {
+    let words = bytes.chunks_exact(WORD);
+    words.remainder().iter().all(|v| *v == 0) && words.map(load).all(|w| w == 0)
+}
+
+pub(crate) fn has_collision(a: &Node, b: &Node, len: usize) -> bool {
+    let len = len.min(a.hash.len()).min(b.hash.len());
+    words_eq(&a.hash[..len], &b.hash[..len])
+}
+
+pub(crate) fn distinct_indices(a: &Node, b: &Node) -> bool {
+    for i in &(a.indices) {
+        for j in &(b.indices) {
+            if i == j {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+fn validate_subtrees(p: &Params, a: &Node, b: &Node) -> Result<(), Kind> {
+    if !has_collision(a, b, p.collision_byte_length()) {
+        Err(Kind::Collision)
+    } else if b.indices_before(a) {
+        Err(Kind::OutOfOrder)
+    } else if !distinct_indices(a, b) {
+        Err(Kind::DuplicateIdxs)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn is_valid_solution_iterative(
+    p: Params,
+    input: &[u8],
+    nonce: &[u8],
+    indices: &[u32],
+) -> Result<(), Error> {
+    let mut state = initialise_state(&p);
+    state.update(input);
+    state.update(nonce);
+
+    let mut rows = Vec::new();
+    for i in indices {
+        rows.push(Node::new(&p, &state, *i));
+    }
+
+    let mut hash_len = p.hash_length();
+    let mut level = 1;
+    while rows.len() > 1 {
+        let width = 1 << (level - 1);
+        let mut cur_rows = Vec::new();
+        for (i, pair) in rows.chunks(2).enumerate() {
+            let a = &pair[0];
+            let b = &pair[1];
+            validate_subtrees(&p, a, b).map_err(|kind| {
+                let left = 2 * i * width;
+                Error::at(
+                    kind,
+                    Location {
+                        level,
+                        left,
+                        right: left + width,
+                    },
+                )
+            })?;
+            cur_rows.push(Node::from_children_ref(a, b, p.collision_byte_length()));
+        }
+        rows = cur_rows;
+        hash_len -= p.collision_byte_length();
+        level += 1;
+    }
+
+    assert!(rows.len() == 1);
+
+    if rows[0].is_zero(hash_len) {
+        Ok(())
+    } else {
+        Err(Error::from(Kind::NonZeroRootHash))
+    }
+}
+
+/// Validates the subtree covering `indices`, which starts at position `offset`
+/// of the full index list.
+#[cfg(test)]
+fn tree_validator(
+    p: &Params,
+    state: &Blake2bState,
+    indices: &[u32],
+    offset: usize,
+) -> Result<Node, Error> {
+    if indices.len() > 1 {
+        let end = indices.len();
+        let mid = end / 2;
+        let a = tree_validator(p, state, &indices[0..mid], offset)?;
+        let b = tree_validator(p, state, &indices[mid..end], offset + mid)?;
+        validate_subtrees(p, &a, &b).map_err(|kind| {
+            Error::at(
+                kind,
+                Location {
+                    level: end.trailing_zeros(),
+                    left: offset,
+                    right: offset + mid,
+                },
+            )
+        })?;
+        Ok(Node::from_children(a, b, p.collision_byte_length()))
+    } else {
+        Ok(Node::new(p, state, indices[0]))
+    }
+}
+
+/// The original node-per-merge verifier, kept as a reference for
+/// [`validate_tree`].
+#[cfg(test)]
+fn is_valid_solution_recursive(
+    p: Params,
+    input: &[u8],
+    nonce: &[u8],
+    indices: &[u32],
+) -> Result<(), Error> {
+    let mut state = initialise_state(&p);
+    state.update(input);
+    state.update(nonce);
+
+    let root = tree_validator(&p, &state, indices, 0)?;
+
+    // Hashes were trimmed, so only need to check remaining length
+    if root.is_zero(p.collision_byte_length()) {
+        Ok(())
+    } else {
+        Err(Error::from(Kind::NonZeroRootHash))
+    }
+}
+
+/// Checks the ordering and distinctness rules of the solution tree using the
+/// indices alone, returning the first merge, in [`validate_tree`]'s order,
+/// that breaks one. Duplicates are found by sorting rather than by comparing
+/// every pair of subtrees.
+fn check_structure(indices: &[u32]) -> Result<(), Error> {
+    let mut stack = [0u32; 512];
+    let mut heap = Vec::new();
+    check_sorted_structure(indices, sort_into(indices, &mut stack, &mut heap))
+}
+
+/// [`check_structure`], given `sorted`, a sorted copy of `indices`.
+fn check_sorted_structure(indices: &[u32], sorted: &[u32]) -> Result<(), Error> {
+    let first_duplicate = if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+        // Two copies of an index first meet in the merge of the smallest
+        // aligned block containing both positions, which completes when its
+        // last leaf is reached. Merges are ordered by that leaf, then by
+        // level; comparing each copy with the next one is enough to find the
+        // earliest.
+        let mut sorted: Vec<(u32, usize)> = indices.iter().copied().zip(0..).collect();
+        sorted.sort_unstable();
+        sorted
+            .windows(2)
+            .filter(|pair| pair[0].0 == pair[1].0)
+            .map(|pair| {
+                let (a, b) = (pair[0].1, pair[1].1);
+                let level = usize::BITS - (a ^ b).leading_zeros();
+                (b | ((1 << level) - 1), level)
+            })
+            .min()
+    } else {
+        None
+    };
+
+    for pos in 0..indices.len() {
+        let mut level = 1;
+        while (pos + 1).is_multiple_of(1 << level) {
+            let width = 1 << (level - 1);
+            let left = pos + 1 - 2 * width;
+            let right = left + width;
+
+            let kind = if indices[right] < indices[left] {
+                Some(Kind::OutOfOrder)
+            } else if first_duplicate == Some((pos, level)) {
+                Some(Kind::DuplicateIdxs)
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                return Err(Error::at(kind, Location { level, left, right }));
+            }
+            level += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Returns a sorted copy of `indices`. Solutions of up to 512 indices, which
+/// covers 200,9, are copied into `stack`, so valid ones are checked without
+/// allocating; longer ones go into `heap`.
+fn sort_into<'a>(indices: &[u32], stack: &'a mut [u32; 512], heap: &'a mut Vec<u32>) -> &'a [u32] {
+    let sorted = if indices.len() <= stack.len() {
+        let sorted = &mut stack[..indices.len()];
+        sorted.copy_from_slice(indices);
+        sorted
+    } else {
+        heap.extend_from_slice(indices);
+        &mut heap[..]
+    };
+    sorted.sort_unstable();
+    sorted
+}
+
+/// The Blake2b outputs a solution's leaves are cut from. Each output holds
+/// `indices_per_hash_output()` leaves (two for 200,9), and one used by more
+/// than one index is hashed once, up front; the rest are hashed as their leaf
+/// is reached. Those outputs are found from the solution's indices in sorted
+/// order, which the duplicate check has already produced.
+struct HashOutputs<'a> {
+    state: &'a Blake2bState,
+    /// The outputs used more than once, sorted by number. Usually empty.
+    shared: Vec<(u32, Blake2bHash)>,
+}
+
+impl<'a> HashOutputs<'a> {
+    /// Finds the shared outputs of a solution whose indices, in sorted
+    /// order, are `sorted`.
+    fn new(p: &Params, state: &'a Blake2bState, sorted: &[u32]) -> Self {
+        let per_output = p.indices_per_hash_output();
+        let mut shared: Vec<(u32, Blake2bHash)> = Vec::new();
+        if per_output > 1 {
+            for pair in sorted.windows(2) {
+                let block = pair[0] / per_output;
+                if block == pair[1] / per_output && shared.last().map(|(b, _)| *b) != Some(block) {
+                    shared.push((block, generate_hash(state, block)));
+                }
+            }
+        }
+        HashOutputs { state, shared }
+    }
+
+    fn get(&self, block: u32) -> Blake2bHash {
+        match self.shared.binary_search_by_key(&block, |(b, _)| *b) {
+            Ok(slot) => self.shared[slot].1,
+            Err(_) => generate_hash(self.state, block),
+        }
+    }
+}
+
+/// Validates the full solution tree against a state that has already absorbed
+/// the input and nonce.
+///
+/// Ordering and duplicates are checked first, from the indices alone, so a
+/// solution that breaks either is rejected before any Blake2b work. Its error
+/// is the first structural failure in tree order, even where an earlier merge
+/// would also have failed its collision check.
+///
+/// Each Blake2b output is then hashed once, however many leaves the solution
+/// cuts from it (see [`HashOutputs`]), and the tree is walked in the same
+/// order as the recursive definition, so the first collision failure found is
+/// the same, but without building a node per merge. Each subtree's hash is kept in the arena slot of its first leaf,
+/// and the bytes consumed by collisions are skipped rather than trimmed. Its
+/// indices need no storage at all: once the structure has been checked, every
+/// merge has the left subtree's first index as the smaller one, so they are
+/// always `indices[left..right]` in their original order.
+fn validate_tree(p: &Params, state: &Blake2bState, indices: &[u32]) -> Result<(), Error> {
+    let mut stack = [0u32; 512];
+    let mut heap = Vec::new();
+    let sorted = sort_into(indices, &mut stack, &mut heap);
+    check_sorted_structure(indices, sorted)?;
+    let outputs = HashOutputs::new(p, state, sorted);
+    validate_leaves(p, indices, &|block| outputs.get(block))
+}
+
+/// Validates the tree of a solution that has passed [`check_structure`], with
+/// the Blake2b output for each leaf supplied by `output`.
+fn validate_leaves<F>(p: &Params, indices: &[u32], output: &F) -> Result<(), Error>
+where
+    F: Fn(u32) -> Blake2bHash + Sync,
+{
+    let mut arena = vec![0u8; indices.len() * p.hash_length()];
+    validate_subtree_from(p, indices, 0, &mut arena, output)?;
+    check_root(p, &arena)
+}
+
+/// Subtrees of at most this many indices are validated on one thread. Below
+/// it, the cost of handing work to the pool outweighs the hashing saved: 200,9
+/// is split into four quarters, while 96,5 and 144,5 are not split at all.
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 128;
+
+/// Validates the subtree covering `indices`, as [`validate_subtree`] does,
+/// with each leaf's Blake2b output from `output`.
+///
+/// With the `parallel` feature, subtrees above [`PARALLEL_THRESHOLD`] are
+/// split in two, and the halves validated on the rayon pool before being
+/// merged. This cuts the latency of a single large verification.
+fn validate_subtree_from<F>(
+    p: &Params,
+    indices: &[u32],
+    offset: usize,
+    arena: &mut [u8],
+    output: &F,
+) -> Result<(), Error>
+where
+    F: Fn(u32) -> Blake2bHash + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        if indices.len() > PARALLEL_THRESHOLD {
+            let mid = indices.len() / 2;
+            let (a, b) = arena.split_at_mut(mid * p.hash_length());
+            let (left, right) = rayon::join(
+                || validate_subtree_from(p, &indices[..mid], offset, a, output),
+                || validate_subtree_from(p, &indices[mid..], offset + mid, b, output),
+            );
+            // The left half comes first in tree order, so its error wins.
+            left?;
+            right?;
+            let level = indices.len().trailing_zeros();
+            return merge(p, arena, 0, level, offset);
+        }
+    }
+
+    validate_subtree(p, indices, offset, arena, output)
+}
+
+/// [`validate_tree`] with the Blake2b output for each leaf supplied by
+/// `output`, which is called with `i / indices_per_hash_output()` for each
+/// index `i`.
+#[cfg(test)]
+fn validate_tree_with<F>(p: &Params, indices: &[u32], output: F) -> Result<(), Error>
+where
+    F: FnMut(u32) -> Blake2bHash,
+{
+    check_structure(indices)?;
+    let mut arena = vec![0u8; indices.len() * p.hash_length()];
+    validate_subtree(p, indices, 0, &mut arena, output)?;
+    check_root(p, &arena)
+}
+
+/// Checks that the root hash, left in the first slot of `arena`, is zero.
+fn check_root(p: &Params, arena: &[u8]) -> Result<(), Error> {
+    // Only the last chunk of the root's hash is left unchecked.
+    let hash_len = p.hash_length();
+    if is_zero(&arena[hash_len - p.collision_byte_length()..hash_len]) {
+        Ok(())
+    } else {
+        Err(Error::from(Kind::NonZeroRootHash))
+    }
+}
+
+/// Validates the subtree covering `indices`, which starts at position `offset`
+/// of the full index list, leaving its hash in the first slot of `arena`.
+fn validate_subtree<F>(
+    p: &Params,
+    indices: &[u32],
+    offset: usize,
+    arena: &mut [u8],
+    mut output: F,
+) -> Result<(), Error>
+where
+    F: FnMut(u32) -> Blake2bHash,
+{
+    let hash_len = p.hash_length();
+    let leaf_len = (p.n as usize) / 8;
+    let per_output = p.indices_per_hash_output();
+
+    for (pos, &i) in indices.iter().enumerate() {
+        let hash = output(i / per_output);
+        let start = ((i % per_output) as usize) * leaf_len;
+        expand_array_into(
+            &hash.as_bytes()[start..start + leaf_len],
+            p.collision_bit_length(),
+            0,
+            &mut arena[pos * hash_len..(pos + 1) * hash_len],
+        );
+
+        // Merge every subtree this leaf completes, smallest first.
+        let mut level = 1;
+        while (pos + 1).is_multiple_of(1 << level) {
+            merge(p, arena, pos + 1 - (1 << level), level, offset)?;
+            level += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Merges the two sibling subtrees at `level` whose left one starts at slot
+/// `left` of `arena`, leaving the result in that slot. `offset` is the
+/// position in the full index list of the arena's first slot.
+fn merge(
+    p: &Params,
+    arena: &mut [u8],
+    left: usize,
+    level: u32,
+    offset: usize,
+) -> Result<(), Error> {
+    let hash_len = p.hash_length();
+    let chunk = p.collision_byte_length();
+    let right = left + (1 << (level - 1));
+    let live = (level as usize - 1) * chunk;
+
+    let (a, b) = arena.split_at_mut(right * hash_len);
+    let a = &mut a[left * hash_len + live..(left + 1) * hash_len];
+    let b = &b[live..hash_len];
+
+    if !words_eq(&a[..chunk], &b[..chunk]) {
+        let location = Location {
+            level,
+            left: offset + left,
+            right: offset + right,
+        };
+        return Err(Error::at(Kind::Collision, location));
+    }
+
+    xor_into(&mut a[chunk..], &b[chunk..]);
+    Ok(())
+}
+
+/// Checks whether `soln` is a valid solution for `(input, nonce)`, starting
+/// from a `base_state` returned by [`initialise_state`] for the same `p`.
+pub(crate) fn is_valid_solution_with_state(
+    p: Params,
+    base_state: &Blake2bState,
+    input: &[u8],
+    nonce: &[u8],
+    soln: &[u8],
+) -> Result<(), Error> {
+    let indices = indices_from_minimal(p, soln)?;
+
+    let mut state = base_state.clone();
+    state.update(input);
+    state.update(nonce);
+
+    validate_tree(&p, &state, &indices)
+}
+
+/// [`is_valid_solution_with_params`] with a Blake2b output hashed for every
+/// index, even where two indices share one. Only the benchmark uses it, to
+/// time the sharing in [`validate_tree`] against it.
+#[cfg(feature = "test-vectors")]
+#[doc(hidden)]
+pub fn is_valid_solution_per_index(
+    p: Params,
+    input: &[u8],
+    nonce: &[u8],
+    soln: &[u8],
+) -> Result<(), Error> {
+    let indices = indices_from_minimal(p, soln)?;
+
+    let mut state = initialise_state(&p);
+    state.update(input);
+    state.update(nonce);
+
+    check_structure(&indices)?;
+    validate_leaves(&p, &indices, &|block| generate_hash(&state, block))
+}
+
+/// Checks whether `soln` is a valid solution for `(input, nonce)` with the
+/// parameters `(n, k)`.
+pub fn is_valid_solution(
+    n: u32,
+    k: u32,
+    input: &[u8],
+    nonce: &[u8],
+    soln: &[u8],
+) -> Result<(), Error> {
+    let p = Params::new(n, k)?;
+    is_valid_solution_with_params(p, input, nonce, soln)
+}
+
+/// Like [`is_valid_solution`], but with the full parameters, including the
+/// personalization.
+pub fn is_valid_solution_with_params(
+    p: Params,
+    input: &[u8],
+    nonce: &[u8],
+    soln: &[u8],
+) -> Result<(), Error> {
+    is_valid_solution_with_state(p, &initialise_state(&p), input, nonce, soln)
+}
+
+/// Modified to take n, k, block_header, and solution as inputs directly.
+pub fn is_valid_solution_direct_input(
+    n: u32,
+    k: u32,
+    block_header_hex: &str,
+    solution_hex: &str,
+) -> Result<(), Error> {
+    // Parse hex strings to byte arrays
+    let input = match hex::decode(block_header_hex) {
+        Ok(bytes) => bytes,
+        Err(_) => return Err(Error::from(Kind::InvalidParams)),
+    };
+
+    let soln = match hex::decode(solution_hex) {
+        Ok(bytes) => bytes,
+        Err(_) => return Err(Error::from(Kind::InvalidParams)),
+    };
+
+    // Original validation logic with parsed byte arrays
+    let p = Params::new(n, k)?;
+    is_valid_solution_with_params(p, &input, &[], &soln)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_structure, compress_array, expand_array, generate_hash, indices_from_minimal,
+        indices_to_minimal, initialise_state, is_valid_solution, is_valid_solution_direct_input,
+        is_valid_solution_iterative, is_valid_solution_recursive, is_valid_solution_with_params,
+        is_zero, validate_tree, validate_tree_with, words_eq, xor_into, Error, HashOutputs, Kind,
+        Location, Params, BGOLD_PERSONALIZATION,
+    };
+
+    fn is_valid_solution_arena(
+        p: Params,
+        input: &[u8],
+        nonce: &[u8],
+        indices: &[u32],
+    ) -> Result<(), Error> {
+        let mut state = initialise_state(&p);
+        state.update(input);
+        state.update(nonce);
+        validate_tree(&p, &state, indices)
+    }
+
+    /// The failure [`validate_tree`] should report for `indices`: the first
+    /// structural one, if any, since those are rejected before hashing, and
+    /// otherwise the recursive verifier's.
+    fn expected_failure(p: Params, input: &[u8], nonce: &[u8], indices: &[u32]) -> Error {
+        let recursive = is_valid_solution_recursive(p, input, nonce, indices).unwrap_err();
+        match check_structure(indices) {
+            Err(structural) => {
+                // Without an earlier collision failure, the recursive
+                // verifier stops at the same merge.
+                if recursive.kind() != Kind::Collision {
+                    assert_eq!(recursive.kind(), structural.kind());
+                    assert_eq!(recursive.location(), structural.location());
+                }
+                structural
+            }
+            Ok(()) => recursive,
+        }
+    }
+
+    use crate::test_vectors::INVALID_TEST_VECTORS;
+    use crate::test_vectors::VALID_TEST_VECTORS;
+
+    #[test]
+    fn params() {
+        Params::new(200, 9).unwrap();
+        Params::new(48, 5).unwrap();
+        for (n, k) in [
+            (96, 4),
+            (100, 4),
+            (96, 2),
+            (8, 8),
+            (1024, 3),
+            (520, 12),
+            (16, 3),
+            (200, 7),
+        ] {
+            assert_eq!(Params::new(n, k).unwrap_err().kind(), Kind::InvalidParams);
+            // Rejected parameters must fail cleanly rather than panic.
+            assert_eq!(
+                is_valid_solution(n, k, b"input", &[], &[0; 64])
+                    .unwrap_err()
+                    .kind(),
+                Kind::InvalidParams
+            );
+        }
+    }
+
+    #[test]
+    fn personalization() {
+        // The test vectors are only valid with Zcash's personalization.
+        for tv in VALID_TEST_VECTORS.iter().take(4) {
+            let p = tv.params.with_personalization(BGOLD_PERSONALIZATION);
+            for soln in tv.solutions {
+                let soln = indices_to_minimal(tv.params, soln).unwrap();
+                is_valid_solution_with_params(tv.params, tv.input, &tv.nonce, &soln).unwrap();
+                is_valid_solution_with_params(p, tv.input, &tv.nonce, &soln).unwrap_err();
+            }
+        }
+    }
+
+    #[test]
+    fn array_expansion() {
+        let check_array = |(bit_len, byte_pad), compact, expanded| {
+            assert_eq!(expand_array(compact, bit_len, byte_pad), expanded);
+            assert_eq!(compress_array(expanded, bit_len, byte_pad), compact);
+        };
+
+        // 8 11-bit chunks, all-ones
+        check_array(
+            (11, 0),
+            &[
+                0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            ],
+            &[
+                0x07, 0xff, 0x07, 0xff, 0x07, 0xff, 0x07, 0xff, 0x07, 0xff, 0x07, 0xff, 0x07, 0xff,
+                0x07, 0xff,
+            ][..],
+        );
+        // 8 21-bit chunks, alternating 1s and 0s
+        check_array(
+            (21, 0),
+            &[
+                0xaa, 0xaa, 0xad, 0x55, 0x55, 0x6a, 0xaa, 0xab, 0x55, 0x55, 0x5a, 0xaa, 0xaa, 0xd5,
+                0x55, 0x56, 0xaa, 0xaa, 0xb5, 0x55, 0x55,
+            ],
+            &[
+                0x15, 0x55, 0x55, 0x15, 0x55, 0x55, 0x15, 0x55, 0x55, 0x15, 0x55, 0x55, 0x15, 0x55,
+                0x55, 0x15, 0x55, 0x55, 0x15, 0x55, 0x55, 0x15, 0x55, 0x55,
+            ][..],
+        );
+        // 8 21-bit chunks, based on example in the spec
+        check_array(
+            (21, 0),
+            &[
+                0x00, 0x02, 0x20, 0x00, 0x0a, 0x7f, 0xff, 0xfe, 0x00, 0x12, 0x30, 0x22, 0xb3, 0x82,
+                0x26, 0xac, 0x19, 0xbd, 0xf2, 0x34, 0x56,
+            ],
+            &[
+                0x00, 0x00, 0x44, 0x00, 0x00, 0x29, 0x1f, 0xff, 0xff, 0x00, 0x01, 0x23, 0x00, 0x45,
+                0x67, 0x00, 0x89, 0xab, 0x00, 0xcd, 0xef, 0x12, 0x34, 0x56,
+            ][..],
+        );
+        // 16 14-bit chunks, alternating 11s and 00s
+        check_array(
+            (14, 0),
+            &[
+                0xcc, 0xcf, 0x33, 0x3c, 0xcc, 0xf3, 0x33, 0xcc, 0xcf, 0x33, 0x3c, 0xcc, 0xf3, 0x33,
+                0xcc, 0xcf, 0x33, 0x3c, 0xcc, 0xf3, 0x33, 0xcc, 0xcf, 0x33, 0x3c, 0xcc, 0xf3, 0x33,
+            ],
+            &[
+                0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33,
+                0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33,
+                0x33, 0x33, 0x33, 0x33,
+            ][..],
+        );
+        // 8 11-bit chunks, all-ones, 2-byte padding
+        check_array(
+            (11, 2),
+            &[
+                0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            ],
+            &[
+                0x00, 0x00, 0x07, 0xff, 0x00, 0x00, 0x07, 0xff, 0x00, 0x00, 0x07, 0xff, 0x00, 0x00,
+                0x07, 0xff, 0x00, 0x00, 0x07, 0xff, 0x00, 0x00, 0x07, 0xff, 0x00, 0x00, 0x07, 0xff,
+                0x00, 0x00, 0x07, 0xff,
+            ][..],
+        );
+    }
+
+    #[test]
+    fn minimal_solution_repr() {
+        let check_repr = |minimal, indices| {
+            assert_eq!(
+                indices_from_minimal(Params::new_unchecked(80, 3), minimal).unwrap(),
+                indices,
+            );
+            assert_eq!(
+                indices_to_minimal(Params::new_unchecked(80, 3), indices).unwrap(),
+                minimal,
+            );
+        };
+
+        // The solutions here are not intended to be valid.
+        check_repr(
+            &[
+                0x00, 0x00, 0x08, 0x00, 0x00, 0x40, 0x00, 0x02, 0x00, 0x00, 0x10, 0x00, 0x00, 0x80,
+                0x00, 0x04, 0x00, 0x00, 0x20, 0x00, 0x01,
+            ],
+            &[1, 1, 1, 1, 1, 1, 1, 1],
+        );
+        check_repr(
+            &[
+                0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            ],
+            &[
+                2097151, 2097151, 2097151, 2097151, 2097151, 2097151, 2097151, 2097151,
+            ],
+        );
+        check_repr(
+            &[
+                0x0f, 0xff, 0xf8, 0x00, 0x20, 0x03, 0xff, 0xfe, 0x00, 0x08, 0x00, 0xff, 0xff, 0x80,
+                0x02, 0x00, 0x3f, 0xff, 0xe0, 0x00, 0x80,
+            ],
+            &[131071, 128, 131071, 128, 131071, 128, 131071, 128],
+        );
+        check_repr(
+            &[
+                0x00, 0x02, 0x20, 0x00, 0x0a, 0x7f, 0xff, 0xfe, 0x00, 0x4d, 0x10, 0x01, 0x4c, 0x80,
+                0x0f, 0xfc, 0x00, 0x00, 0x2f, 0xff, 0xff,
+            ],
+            &[68, 41, 2097151, 1233, 665, 1023, 1, 1048575],
+        );
+    }
+
+    #[test]
+    fn minimal_round_trip() {
+        for tv in VALID_TEST_VECTORS {
+            for soln in tv.solutions {
+                let minimal = indices_to_minimal(tv.params, soln).unwrap();
+                assert_eq!(&indices_from_minimal(tv.params, &minimal).unwrap(), soln);
+            }
+        }
+
+        let p = Params::new_unchecked(80, 3);
+        // Indices must fit in 21 bits.
+        indices_to_minimal(p, &[2097151; 8]).unwrap();
+        indices_to_minimal(p, &[2097152, 1, 1, 1, 1, 1, 1, 1]).unwrap_err();
+        // There must be exactly 2^k of them.
+        indices_to_minimal(p, &[1; 7]).unwrap_err();
+        indices_to_minimal(p, &[1; 9]).unwrap_err();
+    }
+
+    #[test]
+    fn valid_test_vectors() {
+        for tv in VALID_TEST_VECTORS {
+            for soln in tv.solutions {
+                is_valid_solution_iterative(tv.params, tv.input, &tv.nonce, soln).unwrap();
+                is_valid_solution_recursive(tv.params, tv.input, &tv.nonce, soln).unwrap();
+                is_valid_solution_arena(tv.params, tv.input, &tv.nonce, soln).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_test_vectors() {
+        for tv in INVALID_TEST_VECTORS {
+            // The reference verifiers hash before checking the structure, so
+            // they can report a collision where validate_tree does not.
+            assert_eq!(
+                is_valid_solution_iterative(tv.params, tv.input, &tv.nonce, tv.solution)
+                    .unwrap_err()
+                    .kind(),
+                is_valid_solution_recursive(tv.params, tv.input, &tv.nonce, tv.solution)
+                    .unwrap_err()
+                    .kind(),
+            );
+            assert_eq!(
+                expected_failure(tv.params, tv.input, &tv.nonce, tv.solution).kind(),
+                tv.error
+            );
+            assert_eq!(
+                is_valid_solution_arena(tv.params, tv.input, &tv.nonce, tv.solution)
+                    .unwrap_err()
+                    .kind(),
+                tv.error
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_test_vector_locations() {
+        for tv in INVALID_TEST_VECTORS {
+            let iterative =
+                is_valid_solution_iterative(tv.params, tv.input, &tv.nonce, tv.solution)
+                    .unwrap_err();
+            let recursive =
+                is_valid_solution_recursive(tv.params, tv.input, &tv.nonce, tv.solution)
+                    .unwrap_err();
+            let arena =
+                is_valid_solution_arena(tv.params, tv.input, &tv.nonce, tv.solution).unwrap_err();
+            let expected = expected_failure(tv.params, tv.input, &tv.nonce, tv.solution);
+            assert!(recursive.location().is_some());
+            assert_eq!(iterative.location(), recursive.location());
+            assert_eq!(arena.location(), expected.location());
+        }
+
+        let location = |i: usize| {
+            let tv = &INVALID_TEST_VECTORS[i];
+            is_valid_solution_recursive(tv.params, tv.input, &tv.nonce, tv.solution)
+                .unwrap_err()
+                .location()
+                .unwrap()
+        };
+
+        // Reverse the first pair of indices
+        assert_eq!(
+            location(2),
+            Location {
+                level: 1,
+                left: 0,
+                right: 1
+            }
+        );
+        // Swap the first and second pairs of indices
+        assert_eq!(
+            location(3),
+            Location {
+                level: 2,
+                left: 0,
+                right: 2
+            }
+        );
+        // Swap the second-to-last and last pairs of indices
+        assert_eq!(
+            location(4),
+            Location {
+                level: 2,
+                left: 28,
+                right: 30
+            }
+        );
+        // Swap the first half and second half
+        assert_eq!(
+            location(5),
+            Location {
+                level: 5,
+                left: 0,
+                right: 16
+            }
+        );
+    }
+
+    #[test]
+    fn arena_matches_recursive_on_mutations() {
+        // Flip every bit of every index in a few solutions, and check that
+        // both verifiers fail the same way, apart from structural failures
+        // being reported ahead of collisions.
+        for tv in VALID_TEST_VECTORS.iter().take(4) {
+            let bits = tv.params.index_bit_length();
+            for soln in tv.solutions.iter().take(1) {
+                for pos in 0..soln.len() {
+                    for bit in 0..bits {
+                        let mut mutated = soln.to_vec();
+                        mutated[pos] ^= 1 << bit;
+                        let expected = expected_failure(tv.params, tv.input, &tv.nonce, &mutated);
+                        let actual =
+                            is_valid_solution_arena(tv.params, tv.input, &tv.nonce, &mutated)
+                                .unwrap_err();
+                        assert_eq!(actual.kind(), expected.kind());
+                        assert_eq!(actual.location(), expected.location());
+                        is_valid_solution_iterative(tv.params, tv.input, &tv.nonce, &mutated)
+                            .unwrap_err();
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_recursive() {
+        // 200,9 is large enough to be split, so failures in each quarter and
+        // in the merges joining them come from different threads.
+        let tv = VALID_TEST_VECTORS
+            .iter()
+            .find(|tv| tv.params.n == 200)
+            .unwrap();
+        let soln = tv.solutions[0];
+        let check = |mutated: &[u32]| {
+            let expected = expected_failure(tv.params, tv.input, &tv.nonce, mutated);
+            let actual =
+                is_valid_solution_arena(tv.params, tv.input, &tv.nonce, mutated).unwrap_err();
+            assert_eq!(actual.kind(), expected.kind());
+            assert_eq!(actual.location(), expected.location());
+        };
+
+        for pos in (0..soln.len()).step_by(37) {
+            let mut mutated = soln.to_vec();
+            mutated[pos] ^= 1;
+            check(&mutated);
+        }
+        for (a, b) in [(0, 128), (0, 256), (128, 256), (300, 400), (511, 0)] {
+            let mut mutated = soln.to_vec();
+            mutated.swap(a, b);
+            check(&mutated);
+        }
+        for (a, b) in [(5, 133), (130, 500), (256, 257)] {
+            let mut mutated = soln.to_vec();
+            mutated[b] = mutated[a];
+            check(&mutated);
+        }
+    }
+
+    #[test]
+    fn word_ops_match_bytes() {
+        // Every length up to a few words, so that both the word loop and the
+        // leftover bytes are covered.
+        let a: Vec<u8> = (0..40u32).map(|i| (i * 37 + 11) as u8).collect();
+        let b: Vec<u8> = (0..40u32).map(|i| (i * 91 + 5) as u8).collect();
+        for len in 0..=a.len() {
+            let (a, b) = (&a[..len], &b[..len]);
+
+            let mut xored = a.to_vec();
+            xor_into(&mut xored, b);
+            let expected: Vec<u8> = a.iter().zip(b).map(|(a, b)| a ^ b).collect();
+            assert_eq!(xored, expected);
+
+            assert!(words_eq(a, a));
+            assert!(is_zero(&vec![0; len]));
+            for i in 0..len {
+                let mut c = a.to_vec();
+                c[i] ^= 0x80;
+                assert!(!words_eq(a, &c));
+                let mut z = vec![0; len];
+                z[i] = 1;
+                assert!(!is_zero(&z));
+            }
+        }
+    }
+
+    #[test]
+    fn structure() {
+        let ascending: Vec<u32> = (0..32).collect();
+        check_structure(&ascending).unwrap();
+
+        let check = |indices: &[u32]| {
+            let e = check_structure(indices).unwrap_err();
+            (e.kind(), e.location().unwrap())
+        };
+        let at = |level, left, right| Location { level, left, right };
+
+        assert_eq!(check(&[7; 32]), (Kind::DuplicateIdxs, at(1, 0, 1)));
+
+        let descending: Vec<u32> = (0..32).rev().collect();
+        assert_eq!(check(&descending), (Kind::OutOfOrder, at(1, 0, 1)));
+
+        // The two copies of 3 first meet when the halves are merged.
+        let mut far = ascending.clone();
+        far[16] = 3;
+        assert_eq!(check(&far), (Kind::DuplicateIdxs, at(5, 0, 16)));
+
+        // Merges are checked in tree order: the second pair is out of order,
+        // but that is only reached after the duplicate in the first pair.
+        let mut both = ascending.clone();
+        both[1] = 0;
+        both.swap(2, 3);
+        assert_eq!(check(&both), (Kind::DuplicateIdxs, at(1, 0, 1)));
+        both[1] = 1;
+        assert_eq!(check(&both), (Kind::OutOfOrder, at(1, 2, 3)));
+
+        // Longer than the stack copy holds.
+        let mut long: Vec<u32> = (0..1024).collect();
+        check_structure(&long).unwrap();
+        long[1023] = 1022;
+        assert_eq!(check(&long), (Kind::DuplicateIdxs, at(1, 1022, 1023)));
+    }
+
+    #[test]
+    fn structural_failures_skip_hashing() {
+        let p = Params::new(200, 9).unwrap();
+        let state = initialise_state(&p);
+        let hashed = |indices: &[u32]| {
+            let mut calls = 0;
+            let e = validate_tree_with(&p, indices, |block| {
+                calls += 1;
+                generate_hash(&state, block)
+            })
+            .unwrap_err();
+            (e.kind(), calls)
+        };
+
+        let descending: Vec<u32> = (0..512).rev().collect();
+        assert_eq!(hashed(&descending), (Kind::OutOfOrder, 0));
+
+        // Only the halves are out of order, at the last merge.
+        let mut halves: Vec<u32> = (256..512).collect();
+        halves.extend(0..256);
+        assert_eq!(hashed(&halves), (Kind::OutOfOrder, 0));
+
+        let mut late: Vec<u32> = (0..512).map(|i| i * 2).collect();
+        late[501] = late[500];
+        assert_eq!(hashed(&late), (Kind::DuplicateIdxs, 0));
+
+        // A well-formed solution is hashed, and fails on its first collision.
+        let ascending: Vec<u32> = (0..512).collect();
+        let (kind, calls) = hashed(&ascending);
+        assert_eq!(kind, Kind::Collision);
+        assert!(calls > 0);
+    }
+
+    #[test]
+    fn shared_hash_outputs() {
+        // Two indices per output for 200,9.
+        let p = Params::new(200, 9).unwrap();
+        let state = initialise_state(&p);
+        let outputs = HashOutputs::new(&p, &state, &[2, 2, 3, 6, 7, 9]);
+        let shared: Vec<_> = outputs.shared.iter().map(|(block, _)| *block).collect();
+        assert_eq!(shared, [1, 3]);
+        for block in [1, 3, 4, 1] {
+            assert!(outputs.get(block) == generate_hash(&state, block));
+        }
+
+        // 96,5 fits five indices in each output.
+        let p = Params::new(96, 5).unwrap();
+        let state = initialise_state(&p);
+        let outputs = HashOutputs::new(&p, &state, &[0, 5, 9, 10, 14]);
+        let shared: Vec<_> = outputs.shared.iter().map(|(block, _)| *block).collect();
+        assert_eq!(shared, [1, 2]);
+    }
+
+    #[test]
+    fn test_valid_solution_direct_input() {
+        // Define the Equihash parameters, a sample block header, and a solution.
+        let n = 200;
+        let k = 9;
+        let block_header_hex = "0400000008e9694cc2120ec1b5733cc12687b609058eec4f7046a521ad1d1e3049b400003e7420ed6f40659de0305ef9b7ec037f4380ed9848bc1c015691c90aa16ff3930000000000000000000000000000000000000000000000000000000000000000c9310d5874e0001f000000000000000000000000000000010b000000000000000000000000000040";
+        let solution_hex = "00b43863a213bfe79f00337f5a729f09710abcc07035ef8ac34372abddecf2f82715f7223f075af96f0604fc124d6151fc8fb516d24a137faec123a89aa9a433f8a25a6bcfc554c28be556f6c878f96539186fab191505f278df48bf1ad2240e5bb39f372a143de1dd1b672312e00d52a3dd83f471b0239a7e8b30d4b9153027df87c8cd0b64de76749539fea376b4f39d08cf3d5e821495e52fdfa6f8085e59fc670656121c9d7c01388c8b4b4585aa7b9ac3f7ae796f9eb1fadba1730a1860eed797feabb18832b5e8f003c0adaf0788d1016e7a8969144018ecc86140aa4553962aa739a4850b509b505e158c5f9e2d5376374652e9e6d81b19fa0351be229af136efbce681463cc53d7880c1eeca3411154474ff8a7b2bac034a2026646776a517bf63921c31fbbd6be7c3ff42aab28230bfe81d33800b892b262f3579b7a41925a59f5cc1d4f523577c19ff9f92023146fa26486595bd89a1ba459eb0b5cec0578c3a071dbec73eca054c723ab30ce8e69de32e779cd2f1030e39878ac6ea3cdca743b43aedefe1a9b4f2da861038e2759defef0b8cad11d4179f2f08881b53ccc203e558c0571e049d998a257b3279016aad0d7999b609f6331a0d0f88e286a70432ca7f50a5bb8fafbbe9230b4ccb1fa57361c163d6b9f84579d61f41585a022d07dc8e55a8de4d8f87641dae777819458a2bf1bb02c438480ff11621ca8442ec2946875cce247c8877051359e9c822670d37bb00fa806e60e8e890ce62540fda2d5b1c790ca1e005030ac6d8e63db577bb98be111ee146828f9c48ee6257d7627b93ea3dd11aac3412e63dfc7ca132a73c4f51e7650f3f8ecf57bfc18716990b492d50e0a3e5fbf6136e771b91f7283ec3326209265b9531d157f8a07a4117fc8fb29ba1363afc6f9f0608251ea595256727a5bbe28f42a42edfbfa9017680e32980d4ad381612612b2bc7ad91e82eca693ea4fc27049a99636b50a576f1e55c72202d582b150ef194c1419f53177ecf315ea6b0e2f1aa8cd8f59b165aa0d89561c537fb6141f5813b7a4968fe16afc703326113f68508d88ff8d0aee1e88a84c0ae56c72f27511290ced48e93e8c95419d14aed1a5b2e9b2c9c1070c593e5eb50bb9a80e14e9f9fe501f56b1b3140159e8213b75d48d14af472a604484cd8e7e7abb6820245ed3ab29f9947463a033c586194be45eadec8392c8614d83a1e9ca0fe5655fa14f7a9c1d1f8f2185a06193ff4a3c3e9a96b02310033ceaa25894e7c56a6147e691597098054e285d39656d3d459ec5d13243c062b6eb44e19a13bdfc0b3c96bd3d1aeb75bb6b080322aea23555993cb529243958bb1a0e5d5027e6c78155437242d1d13c1d6e442a0e3783147a08bbfc0c2529fb705ad27713df40486fd58f001977f25dfd3c202451c07010a3880bca63959ca61f10ed3871f1152166fce2b52135718a8ceb239a0664a31c62defaad70be4b920dce70549c10d9138fbbad7f291c5b73fa21c3889929b143bc1576b72f70667ac11052b686891085290d871db528b5cfdc10a6d563925227609f10d1768a0e02dc7471ad424f94f737d4e7eb0fb167f1434fc4ae2d49e152f06f0845b6db0a44f0d6f5e7410420e6bd1f430b1af956005bf72b51405a04d9a5d9906ceca52c22c855785c3c3ac4c3e9bf532d31bab321e1db66f6a9f7dc9c017f2b7d8dfeb933cf5bbae71311ae318f6d187ebc5c843be342b08a9a0ff7c4b9c4b0f4fa74b13296afe84b6481440d58332e07b3d051ed55219d28e77af6612134da4431b797c63ef55bc53831e2f421db620fee51ba0967e4ed7009ef90af2204259bbfbb54537fd35c2132fa8e7f9c84bf9938d248862c6ca1cca9f48b0b33aa1589185c4eabc1c32";
+
+        // Call the function with the test parameters
+        let result = is_valid_solution_direct_input(n, k, block_header_hex, solution_hex);
+
+        // Assert the expected outcome (here we assume the solution is valid)
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn all_bits_matter() {
+        // Initialize the state according to one of the valid test vectors.
+        let n = 96;
+        let k = 5;
+        let input = b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.";
+        let nonce = [
+            1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0,
+        ];
+        let soln = &[
+            0x04, 0x6a, 0x8e, 0xd4, 0x51, 0xa2, 0x19, 0x73, 0x32, 0xe7, 0x1f, 0x39, 0xdb, 0x9c,
+            0x79, 0xfb, 0xf9, 0x3f, 0xc1, 0x44, 0x3d, 0xa5, 0x8f, 0xb3, 0x8d, 0x05, 0x99, 0x17,
+            0x21, 0x16, 0xd5, 0x55, 0xb1, 0xb2, 0x1f, 0x32, 0x70, 0x5c, 0xe9, 0x98, 0xf6, 0x0d,
+            0xa8, 0x52, 0xf7, 0x7f, 0x0e, 0x7f, 0x4d, 0x63, 0xfc, 0x2d, 0xd2, 0x30, 0xa3, 0xd9,
+            0x99, 0x53, 0xa0, 0x78, 0x7d, 0xfe, 0xfc, 0xab, 0x34, 0x1b, 0xde, 0xc8,
+        ];
+
+        // Prove that the solution is valid.
+        is_valid_solution(n, k, input, &nonce, soln).unwrap();
+
+        // Changing any single bit of the encoded solution should make it invalid.
+        for i in 0..soln.len() * 8 {
+            let mut mutated = soln.to_vec();
+            mutated[i / 8] ^= 1 << (i % 8);
+            is_valid_solution(n, k, input, &nonce, &mutated).unwrap_err();
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Python bindings, built with maturin as the `equihash` module. Binary
+//! arguments and header fields are `bytes`.
+
+#![cfg(feature = "python")]
+
+use equihash::header::BlockHeader;
+use equihash::verify::{self, Params, PERSONALIZATION_PRESETS, ZCASH_PERSONALIZATION};
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use std::convert::TryInto;
+
+create_exception!(
+    equihash,
+    InvalidSolution,
+    PyValueError,
+    "The solution was rejected. `code` is the failure kind, such as \
+     `COLLISION`; `level`, `left` and `right` locate the failing merge, or are \
+     None."
+);
+create_exception!(
+    equihash,
+    HeaderError,
+    PyValueError,
+    "The header could not be parsed."
+);
+
+fn params(n: u32, k: u32) -> PyResult<Params> {
+    Params::new(n, k).map_err(|_| {
+        PyValueError::new_err(format!("unsupported Equihash parameters n={}, k={}", n, k))
+    })
+}
+
+/// Either a name from `PERSONALIZATION_PRESETS`, or the 8-byte prefix as
+/// `str` or `bytes`. Defaults to Zcash's.
+fn personalization(value: Option<&Bound<'_, PyAny>>) -> PyResult<[u8; 8]> {
+    let value = match value {
+        Some(value) if !value.is_none() => value,
+        _ => return Ok(ZCASH_PERSONALIZATION),
+    };
+    let bytes = match value.extract::<String>() {
+        Ok(name) => {
+            if let Some((_, preset)) = PERSONALIZATION_PRESETS.iter().find(|(n, _)| *n == name) {
+                return Ok(*preset);
+            }
+            name.into_bytes()
+        }
+        Err(_) => value.extract::<Vec<u8>>()?,
+    };
+    bytes.as_slice().try_into().map_err(|_| {
+        PyValueError::new_err(format!(
+            "personalization: expected a preset name or 8 bytes, got {}",
+            value
+                .repr()
+                .map_or_else(|_| "?".to_string(), |r| r.to_string())
+        ))
+    })
+}
+
+fn check_solution_len(p: Params, solution: &[u8]) -> PyResult<()> {
+    if solution.len() != p.solution_len() {
+        return Err(PyValueError::new_err(format!(
+            "solution must be {} bytes for these parameters, got {}",
+            p.solution_len(),
+            solution.len()
+        )));
+    }
+    Ok(())
+}
+
+fn invalid_solution(py: Python<'_>, e: verify::Error) -> PyResult<PyErr> {
+    let err = InvalidSolution::new_err(e.to_string());
+    let value = err.value(py);
+    value.setattr("code", e.kind().code())?;
+    let location = e.location();
+    value.setattr("level", location.map(|l| l.level))?;
+    value.setattr("left", location.map(|l| l.left))?;
+    value.setattr("right", location.map(|l| l.right))?;
+    Ok(err)
+}
+
+/// Checks `solution` for `input` followed by `nonce`, raising
+/// `InvalidSolution` if it is rejected.
+#[pyfunction]
+#[pyo3(name = "verify", signature = (n, k, input, nonce, solution, personalization=None))]
+fn verify_solution(
+    py: Python<'_>,
+    n: u32,
+    k: u32,
+    input: &[u8],
+    nonce: &[u8],
+    solution: &[u8],
+    personalization: Option<&Bound<'_, PyAny>>,
+) -> PyResult<()> {
+    let p = params(n, k)?.with_personalization(self::personalization(personalization)?);
+    check_solution_len(p, solution)?;
+    let result = py.detach(|| verify::is_valid_solution_with_params(p, input, nonce, solution));
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => Err(invalid_solution(py, e)?),
+    }
+}
+
+/// Like `verify`, but returns whether the solution is valid.
+#[pyfunction]
+#[pyo3(signature = (n, k, input, nonce, solution, personalization=None))]
+fn is_valid_solution(
+    py: Python<'_>,
+    n: u32,
+    k: u32,
+    input: &[u8],
+    nonce: &[u8],
+    solution: &[u8],
+    personalization: Option<&Bound<'_, PyAny>>,
+) -> PyResult<bool> {
+    let p = params(n, k)?.with_personalization(self::personalization(personalization)?);
+    check_solution_len(p, solution)?;
+    Ok(py
+        .detach(|| verify::is_valid_solution_with_params(p, input, nonce, solution))
+        .is_ok())
+}
+
+/// Decodes a minimal solution into its indices.
+#[pyfunction]
+fn indices_from_minimal(n: u32, k: u32, solution: &[u8]) -> PyResult<Vec<u32>> {
+    let p = params(n, k)?;
+    check_solution_len(p, solution)?;
+    verify::indices_from_minimal(p, solution).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Encodes indices into the minimal solution form.
+#[pyfunction]
+fn indices_to_minimal<'py>(
+    py: Python<'py>,
+    n: u32,
+    k: u32,
+    indices: Vec<u32>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let p = params(n, k)?;
+    match verify::indices_to_minimal(p, &indices) {
+        Ok(minimal) => Ok(PyBytes::new(py, &minimal)),
+        Err(_) => Err(PyValueError::new_err(format!(
+            "indices must be {} values of at most {} bits",
+            p.solution_indices(),
+            p.index_bit_length()
+        ))),
+    }
+}
+
+/// Finds the solutions for `input` followed by `nonce`. Only practical for
+/// small parameters such as 48,5 or 96,5.
+#[pyfunction]
+#[pyo3(signature = (n, k, input, nonce, personalization=None))]
+fn solve(
+    py: Python<'_>,
+    n: u32,
+    k: u32,
+    input: &[u8],
+    nonce: &[u8],
+    personalization: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Vec<Vec<u32>>> {
+    let p = params(n, k)?.with_personalization(self::personalization(personalization)?);
+    Ok(py.detach(|| equihash::solve::solve(p, input, nonce)))
+}
+
+/// Converts a header into a dict with the fields named as in Rust. Hashes
+/// are in serialized byte order.
+fn header_to_dict<'py>(py: Python<'py>, header: &BlockHeader) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("version", header.version)?;
+    dict.set_item("prev_hash", PyBytes::new(py, &header.prev_hash))?;
+    dict.set_item("merkle_root", PyBytes::new(py, &header.merkle_root))?;
+    dict.set_item(
+        "final_sapling_root",
+        PyBytes::new(py, &header.final_sapling_root),
+    )?;
+    dict.set_item("time", header.time)?;
+    dict.set_item("bits", header.bits)?;
+    dict.set_item("nonce", PyBytes::new(py, &header.nonce))?;
+    dict.set_item("solution", PyBytes::new(py, &header.solution))?;
+    dict.set_item("pastel_id", &header.pastel_id)?;
+    dict.set_item("signature", PyBytes::new(py, &header.signature))?;
+    Ok(dict)
+}
+
+/// Parses a serialized Pastel header. Raises `HeaderError` if the data is
+/// malformed or has bytes left over.
+#[pyfunction]
+fn parse_header<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyDict>> {
+    let header = BlockHeader::parse(data).map_err(|e| HeaderError::new_err(e.to_string()))?;
+    header_to_dict(py, &header)
+}
+
+/// Parses the header at the start of a raw block. The dict also has `size`,
+/// the header's length in bytes.
+#[pyfunction]
+fn parse_block_header<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyDict>> {
+    let (header, size) =
+        BlockHeader::read(data).map_err(|e| HeaderError::new_err(e.to_string()))?;
+    let dict = header_to_dict(py, &header)?;
+    dict.set_item("size", size)?;
+    Ok(dict)
+}
+
+/// Checks a serialized header's own solution against its Pastel Equihash
+/// input, raising `InvalidSolution` if it is rejected.
+#[pyfunction]
+#[pyo3(signature = (data, n=200, k=9))]
+fn verify_header(py: Python<'_>, data: &[u8], n: u32, k: u32) -> PyResult<()> {
+    params(n, k)?;
+    let header = BlockHeader::parse(data).map_err(|e| HeaderError::new_err(e.to_string()))?;
+    match py.detach(|| header.verify_solution(n, k)) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(invalid_solution(py, e)?),
+    }
+}
+
+/// `(n, k, input, nonce, solutions)`, as passed to `verify`.
+#[cfg(feature = "test-vectors")]
+type TestVector<'py> = (
+    u32,
+    u32,
+    Bound<'py, PyBytes>,
+    Bound<'py, PyBytes>,
+    Vec<Vec<u32>>,
+);
+
+/// The known-good test vectors.
+#[cfg(feature = "test-vectors")]
+#[pyfunction]
+fn _valid_test_vectors(py: Python<'_>) -> Vec<TestVector<'_>> {
+    equihash::test_vectors::VALID_TEST_VECTORS
+        .iter()
+        .map(|tv| {
+            (
+                tv.params.n(),
+                tv.params.k(),
+                PyBytes::new(py, tv.input),
+                PyBytes::new(py, &tv.nonce),
+                tv.solutions.iter().map(|s| s.to_vec()).collect(),
+            )
+        })
+        .collect()
+}
+
+/// A raw Pastel v5 block, header first.
+#[cfg(feature = "test-vectors")]
+#[pyfunction]
+fn _pastel_v5_block(py: Python<'_>) -> PyResult<Bound<'_, PyBytes>> {
+    let block = hex::decode(equihash::test_vectors::PASTEL_V5_BLOCK)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyBytes::new(py, &block))
+}
+
+#[pymodule]
+#[pyo3(name = "equihash")]
+fn equihash_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("InvalidSolution", m.py().get_type::<InvalidSolution>())?;
+    m.add("HeaderError", m.py().get_type::<HeaderError>())?;
+    m.add_function(wrap_pyfunction!(verify_solution, m)?)?;
+    m.add_function(wrap_pyfunction!(is_valid_solution, m)?)?;
+    m.add_function(wrap_pyfunction!(indices_from_minimal, m)?)?;
+    m.add_function(wrap_pyfunction!(indices_to_minimal, m)?)?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_header, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_block_header, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_header, m)?)?;
+    #[cfg(feature = "test-vectors")]
+    {
+        m.add_function(wrap_pyfunction!(_valid_test_vectors, m)?)?;
+        m.add_function(wrap_pyfunction!(_pastel_v5_block, m)?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+"""Runs the Rust test vectors through the module.
+
+Build with the test vectors first:
+
+    maturin develop --features python,test-vectors
+    pytest tests
+"""
+
+import pytest
+
+import equihash
+
+if not hasattr(equihash, "_valid_test_vectors"):
+    pytest.skip(
+        "built without the test-vectors feature", allow_module_level=True
+    )
+
+VECTORS = equihash._valid_test_vectors()
+
+
+def first_solution():
+    n, k, input, nonce, solutions = VECTORS[0]
+    return n, k, input, nonce, equihash.indices_to_minimal(n, k, solutions[0])
+
+
+@pytest.mark.parametrize("n,k,input,nonce,solutions", VECTORS)
+def test_valid_test_vectors(n, k, input, nonce, solutions):
+    for indices in solutions:
+        minimal = equihash.indices_to_minimal(n, k, indices)
+        equihash.verify(n, k, input, nonce, minimal)
+        assert equihash.is_valid_solution(n, k, input, nonce, minimal)
+        assert equihash.indices_from_minimal(n, k, minimal) == list(indices)
+
+
+def test_rejected_solution():
+    n, k, input, nonce, minimal = first_solution()
+    mutated = bytes([minimal[0] ^ 1]) + minimal[1:]
+    assert not equihash.is_valid_solution(n, k, input, nonce, mutated)
+    with pytest.raises(equihash.InvalidSolution) as e:
+        equihash.verify(n, k, input, nonce, mutated)
+    assert e.value.code in ("COLLISION", "OUT_OF_ORDER")
+    assert e.value.level is not None
+
+
+def test_personalization():
+    n, k, input, nonce, minimal = first_solution()
+    equihash.verify(n, k, input, nonce, minimal, "zcash")
+    equihash.verify(n, k, input, nonce, minimal, b"ZcashPoW")
+    assert not equihash.is_valid_solution(n, k, input, nonce, minimal, "bgold")
+    with pytest.raises(ValueError):
+        equihash.verify(n, k, input, nonce, minimal, "short")
+
+
+def test_solve():
+    vector = next((v for v in VECTORS if v[0] == 96 and v[1] == 5), None)
+    assert vector is not None, "no 96,5 test vector"
+    n, k, input, nonce, solutions = vector
+    assert [list(s) for s in solutions] == equihash.solve(n, k, input, nonce)
+
+
+def test_argument_errors():
+    n, k, input, nonce, minimal = first_solution()
+    with pytest.raises(ValueError, match="unsupported Equihash parameters"):
+        equihash.verify(200, 8, input, nonce, minimal)
+    with pytest.raises(ValueError, match="solution must be"):
+        equihash.verify(n, k, input, nonce, minimal[:-1])
+    with pytest.raises(TypeError):
+        equihash.verify(n, k, input.hex(), nonce, minimal)
+
+
+def test_pastel_header():
+    block = equihash._pastel_v5_block()
+    header = equihash.parse_block_header(block)
+    size = header.pop("size")
+    assert header["version"] == 5
+    assert header["pastel_id"]
+    assert equihash.parse_header(block[:size]) == header
+    equihash.verify_header(block[:size])
+    with pytest.raises(equihash.HeaderError):
+        equihash.parse_header(block)
@@ -1,19 +1,30 @@
+use equihash::batch::{verify_batch, BatchItem};
+use equihash::context::HeaderContext;
+use equihash::header::BlockHeader;
+use equihash::solve::solve;
+use equihash::verify::{
+    indices_from_minimal, indices_to_minimal, is_valid_solution_with_params, Params,
+    PERSONALIZATION_PRESETS, ZCASH_PERSONALIZATION,
+};
+use equihash::{header, pow, verify};
 use neon::prelude::*;
-mod verify;
-mod test_vectors;
-use verify::is_valid_solution_direct_input; // Ensure this is the function we are using now.
+use neon::types::buffer::TypedArray;
+use std::convert::TryInto;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 fn is_valid_solution_wrapper(mut cx: FunctionContext) -> JsResult<JsBoolean> {
-    // Extract n and k parameters from JavaScript arguments.
-    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
-    let k = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    // Extract and check n and k parameters from JavaScript arguments.
+    let p = params_argument(&mut cx, 0)?;
 
-    // Extract the block header and solution as hex strings from JavaScript arguments.
-    let block_header_hex = cx.argument::<JsString>(2)?.value(&mut cx);
-    let solution_hex = cx.argument::<JsString>(3)?.value(&mut cx);
+    // Extract the block header and solution, as hex strings or byte arrays.
+    let input = bytes_argument(&mut cx, 2, "blockHeader")?;
+    let soln = solution_argument(&mut cx, 3, p)?;
 
-    // Directly call the Rust is_valid_solution_direct_input function.
-    let result = is_valid_solution_direct_input(n, k, &block_header_hex, &solution_hex);
+    let result = catch_panic(|| {
+        is_valid_solution_with_params(p, input.as_slice(&cx), &[], soln.as_slice(&cx))
+    });
+    let result = rethrow(&mut cx, result)?;
 
     // Map the result to a JavaScript boolean value.
     match result {
@@ -22,7 +33,762 @@ fn is_valid_solution_wrapper(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     }
 }
 
-// Register the module and export the is_valid_solution_wrapper function to JavaScript.
-register_module!(mut m, {
-    m.export_function("is_validSolution", is_valid_solution_wrapper)
-});
+fn is_valid_solution_with_nonce_wrapper(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    // Like is_validSolution, but the nonce is passed separately instead of
+    // being appended to the header.
+    let p = params_argument(&mut cx, 0)?;
+    let input = bytes_argument(&mut cx, 2, "input")?;
+    let nonce = bytes_argument(&mut cx, 3, "nonce")?;
+    let soln = solution_argument(&mut cx, 4, p)?;
+
+    let result = catch_panic(|| {
+        verify::is_valid_solution(
+            p.n(),
+            p.k(),
+            input.as_slice(&cx),
+            nonce.as_slice(&cx),
+            soln.as_slice(&cx),
+        )
+    });
+    let result = rethrow(&mut cx, result)?;
+    Ok(cx.boolean(result.is_ok()))
+}
+
+/// Runs `f`, returning the panic message instead if it panics.
+fn catch_panic<T, F: FnOnce() -> T>(f: F) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        }
+    })
+}
+
+/// Runs `f`, turning a panic into a JavaScript exception. Neon would catch it
+/// too, but without saying where it came from.
+fn guard<'a, C: Context<'a>, T, F: FnOnce() -> T>(cx: &mut C, f: F) -> NeonResult<T> {
+    rethrow(cx, catch_panic(f))
+}
+
+/// Throws a panic message caught by `catch_panic`. Used instead of `guard`
+/// when the closure needs to borrow the context.
+fn rethrow<'a, C: Context<'a>, T>(cx: &mut C, result: Result<T, String>) -> NeonResult<T> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(message) => cx.throw_error(format!("equihash panicked: {}", message)),
+    }
+}
+
+/// Reads `value` as an integer that fits in a `u32`.
+fn u32_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    value: Handle<'a, JsValue>,
+    name: &str,
+) -> NeonResult<u32> {
+    let number = match value.downcast::<JsNumber, _>(cx) {
+        Ok(number) => number.value(cx),
+        Err(_) => return cx.throw_type_error(format!("{} must be a number", name)),
+    };
+    if !(0.0..=f64::from(u32::MAX)).contains(&number) || number.fract() != 0.0 {
+        return cx.throw_range_error(format!(
+            "{} must be an integer from 0 to {}, got {}",
+            name,
+            u32::MAX,
+            number
+        ));
+    }
+    Ok(number as u32)
+}
+
+/// Binary data passed in from JavaScript: either a `Buffer`/`Uint8Array`,
+/// borrowed in place, or bytes decoded from a hex string.
+enum Bytes<'a> {
+    Borrowed(Handle<'a, JsTypedArray<u8>>),
+    Decoded(Vec<u8>),
+}
+
+impl<'a> Bytes<'a> {
+    fn as_slice<'b, C: Context<'a>>(&'b self, cx: &'b C) -> &'b [u8] {
+        match self {
+            Bytes::Borrowed(array) => array.as_slice(cx),
+            Bytes::Decoded(bytes) => bytes,
+        }
+    }
+
+    /// Takes ownership of the bytes, copying them out of a borrowed array.
+    /// Needed when they outlive the call, e.g. on the thread pool.
+    fn into_vec<C: Context<'a>>(self, cx: &C) -> Vec<u8> {
+        match self {
+            Bytes::Borrowed(array) => array.as_slice(cx).to_vec(),
+            Bytes::Decoded(bytes) => bytes,
+        }
+    }
+}
+
+/// Reads `value` as a `Buffer`/`Uint8Array` or a hex string.
+fn bytes_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    value: Handle<'a, JsValue>,
+    name: &str,
+) -> NeonResult<Bytes<'a>> {
+    if let Ok(array) = value.downcast::<JsTypedArray<u8>, _>(cx) {
+        // Neon 0.10 builds the slice from a null pointer for empty arrays,
+        // so those never get borrowed.
+        let length = array.get::<JsNumber, _, _>(cx, "length")?.value(cx);
+        if length == 0.0 {
+            return Ok(Bytes::Decoded(Vec::new()));
+        }
+        return Ok(Bytes::Borrowed(array));
+    }
+    let value = match value.downcast::<JsString, _>(cx) {
+        Ok(value) => value.value(cx),
+        Err(_) => {
+            return cx.throw_type_error(format!(
+                "{} must be a hex string, Buffer or Uint8Array",
+                name
+            ))
+        }
+    };
+    match hex::decode(&value) {
+        Ok(bytes) => Ok(Bytes::Decoded(bytes)),
+        Err(hex::FromHexError::OddLength) => cx.throw_range_error(format!(
+            "{} must have an even number of hex digits, got {}",
+            name,
+            value.len()
+        )),
+        Err(hex::FromHexError::InvalidHexCharacter { c, index }) => cx.throw_type_error(format!(
+            "{} has non-hex character {:?} at position {}",
+            name, c, index
+        )),
+        Err(e) => cx.throw_type_error(format!("{}: {}", name, e)),
+    }
+}
+
+/// Reads `n` and `k`, throwing unless the verifier supports them.
+fn params_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    n: Handle<'a, JsValue>,
+    k: Handle<'a, JsValue>,
+) -> NeonResult<Params> {
+    let n = u32_from_js(cx, n, "n")?;
+    let k = u32_from_js(cx, k, "k")?;
+    match Params::new(n, k) {
+        Ok(p) => Ok(p),
+        Err(_) => cx.throw_range_error(format!("unsupported Equihash parameters n={}, k={}", n, k)),
+    }
+}
+
+/// Throws unless `len` is the minimal encoding's length for `p`.
+fn check_solution_len<'a, C: Context<'a>>(
+    cx: &mut C,
+    p: Params,
+    len: usize,
+    name: &str,
+) -> NeonResult<()> {
+    if len != p.solution_len() {
+        return cx.throw_range_error(format!(
+            "{} must be {} bytes for these parameters, got {}",
+            name,
+            p.solution_len(),
+            len
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the `n` and `k` arguments at positions `i` and `i + 1`.
+fn params_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<Params> {
+    let n = cx.argument::<JsValue>(i)?;
+    let k = cx.argument::<JsValue>(i + 1)?;
+    params_from_js(cx, n, k)
+}
+
+/// Reads the binary argument at position `i`.
+fn bytes_argument<'a>(cx: &mut FunctionContext<'a>, i: i32, name: &str) -> NeonResult<Bytes<'a>> {
+    let value = cx.argument::<JsValue>(i)?;
+    bytes_from_js(cx, value, name)
+}
+
+/// Reads the minimal solution argument at position `i` and checks its length.
+fn solution_argument<'a>(cx: &mut FunctionContext<'a>, i: i32, p: Params) -> NeonResult<Bytes<'a>> {
+    let soln = bytes_argument(cx, i, "solution")?;
+    let len = soln.as_slice(cx).len();
+    check_solution_len(cx, p, len, "solution")?;
+    Ok(soln)
+}
+
+/// Builds the `{ valid: false, code, message }` object shared by every failure.
+fn failure_object<'a, C: Context<'a>>(
+    cx: &mut C,
+    code: &str,
+    message: &str,
+) -> JsResult<'a, JsObject> {
+    let obj = cx.empty_object();
+    let valid = cx.boolean(false);
+    obj.set(cx, "valid", valid)?;
+    let code = cx.string(code);
+    obj.set(cx, "code", code)?;
+    let message = cx.string(message);
+    obj.set(cx, "message", message)?;
+    Ok(obj)
+}
+
+/// Builds the `{ valid, code, message, level, left, right }` object describing
+/// a verification result.
+fn verification_to_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    result: &Result<(), verify::Error>,
+) -> JsResult<'a, JsObject> {
+    match result {
+        Ok(()) => {
+            let obj = cx.empty_object();
+            let valid = cx.boolean(true);
+            obj.set(cx, "valid", valid)?;
+            Ok(obj)
+        }
+        Err(e) => {
+            let obj = failure_object(cx, e.kind().code(), &e.to_string())?;
+            if let Some(location) = e.location() {
+                let level = cx.number(location.level);
+                obj.set(cx, "level", level)?;
+                let left = cx.number(location.left as f64);
+                obj.set(cx, "left", left)?;
+                let right = cx.number(location.right as f64);
+                obj.set(cx, "right", right)?;
+            }
+            Ok(obj)
+        }
+    }
+}
+
+/// Reads an optional personalization argument: either a name from
+/// `PERSONALIZATION_PRESETS` or the 8-character prefix itself. Names are
+/// checked first. Defaults to Zcash's prefix when missing or undefined.
+fn personalization_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    value: Option<Handle<'a, JsValue>>,
+) -> NeonResult<[u8; 8]> {
+    let value = match value {
+        Some(v) if !v.is_a::<JsUndefined, _>(cx) => v.downcast_or_throw::<JsString, _>(cx)?,
+        _ => return Ok(ZCASH_PERSONALIZATION),
+    };
+    let value = value.value(cx);
+
+    if let Some((_, preset)) = PERSONALIZATION_PRESETS
+        .iter()
+        .find(|(name, _)| *name == value)
+    {
+        return Ok(*preset);
+    }
+    match value.as_bytes().try_into() {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => cx.throw_range_error(format!(
+            "personalization: expected a preset name or 8 bytes, got {:?}",
+            value
+        )),
+    }
+}
+
+fn verify_solution_detailed_wrapper(mut cx: FunctionContext) -> JsResult<JsObject> {
+    // Same arguments as is_validSolution.
+    let p = params_argument(&mut cx, 0)?;
+    let input = bytes_argument(&mut cx, 2, "blockHeader")?;
+    let soln = solution_argument(&mut cx, 3, p)?;
+    let personalization = cx.argument_opt(4);
+    let p = p.with_personalization(personalization_from_js(&mut cx, personalization)?);
+
+    let result = catch_panic(|| {
+        is_valid_solution_with_params(p, input.as_slice(&cx), &[], soln.as_slice(&cx))
+    });
+    let result = rethrow(&mut cx, result)?;
+    verification_to_js(&mut cx, &result)
+}
+
+/// A `HeaderContext` owned by a JavaScript object, so the midstate lives as
+/// long as the `HeaderContext` class instance wrapping it in `index.js`.
+struct BoxedHeaderContext(HeaderContext);
+
+impl Finalize for BoxedHeaderContext {}
+
+fn header_context_new_wrapper(mut cx: FunctionContext) -> JsResult<JsBox<BoxedHeaderContext>> {
+    // (n, k, header, personalization), where the header excludes the nonce.
+    let p = params_argument(&mut cx, 0)?;
+    let input = bytes_argument(&mut cx, 2, "header")?;
+    let personalization = cx.argument_opt(3);
+    let p = p.with_personalization(personalization_from_js(&mut cx, personalization)?);
+
+    let ctx = catch_panic(|| HeaderContext::new(p, input.as_slice(&cx)));
+    let ctx = rethrow(&mut cx, ctx)?;
+    Ok(cx.boxed(BoxedHeaderContext(ctx)))
+}
+
+/// Checks the `(nonce, solution)` arguments at positions 1 and 2 against the
+/// boxed context at position 0.
+fn header_context_verify(cx: &mut FunctionContext) -> NeonResult<Result<(), verify::Error>> {
+    let ctx = cx.argument::<JsBox<BoxedHeaderContext>>(0)?;
+    let nonce = bytes_argument(cx, 1, "nonce")?;
+    let soln = solution_argument(cx, 2, ctx.0.params())?;
+
+    let result = catch_panic(|| ctx.0.verify(nonce.as_slice(cx), soln.as_slice(cx)));
+    rethrow(cx, result)
+}
+
+fn header_context_verify_wrapper(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let result = header_context_verify(&mut cx)?;
+    Ok(cx.boolean(result.is_ok()))
+}
+
+fn header_context_verify_detailed_wrapper(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let result = header_context_verify(&mut cx)?;
+    verification_to_js(&mut cx, &result)
+}
+
+/// Default cap on verifications queued or running on the libuv thread pool.
+const DEFAULT_MAX_IN_FLIGHT: usize = 1024;
+
+static MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_IN_FLIGHT);
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// A reserved slot for one background verification, released when dropped.
+struct InFlightSlot;
+
+impl InFlightSlot {
+    fn acquire() -> Option<Self> {
+        let max = MAX_IN_FLIGHT.load(Ordering::Relaxed);
+        IN_FLIGHT
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                if n < max {
+                    Some(n + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()
+            .map(|_| InFlightSlot)
+    }
+}
+
+impl Drop for InFlightSlot {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn verify_solution_async_wrapper(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    // Same arguments as is_validSolution, checked before anything is queued.
+    let p = params_argument(&mut cx, 0)?;
+    // The task outlives this call, so buffers are copied.
+    let input = bytes_argument(&mut cx, 2, "blockHeader")?.into_vec(&cx);
+    let soln = solution_argument(&mut cx, 3, p)?.into_vec(&cx);
+    let personalization = cx.argument_opt(4);
+    let p = p.with_personalization(personalization_from_js(&mut cx, personalization)?);
+
+    // Reject straight away rather than queueing without bound behind libuv.
+    let slot = match InFlightSlot::acquire() {
+        Some(slot) => slot,
+        None => {
+            let (deferred, promise) = cx.promise();
+            let err = cx.error(format!(
+                "too many verifications in flight (limit {})",
+                MAX_IN_FLIGHT.load(Ordering::Relaxed)
+            ))?;
+            deferred.reject(&mut cx, err);
+            return Ok(promise);
+        }
+    };
+
+    // Verification runs on the libuv thread pool; only the boolean conversion
+    // happens back on the main thread. A panic rejects the promise.
+    let promise = cx
+        .task(move || {
+            let valid =
+                catch_panic(|| is_valid_solution_with_params(p, &input, &[], &soln).is_ok());
+            drop(slot);
+            valid
+        })
+        .promise(|mut cx, valid| match valid {
+            Ok(valid) => Ok(cx.boolean(valid)),
+            Err(message) => cx.throw_error(format!("equihash panicked: {}", message)),
+        });
+
+    Ok(promise)
+}
+
+fn set_max_in_flight_wrapper(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let limit = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    if !(limit >= 1.0 && limit.fract() == 0.0) {
+        return cx.throw_range_error("maxInFlight must be a positive integer");
+    }
+    MAX_IN_FLIGHT.store(limit as usize, Ordering::Relaxed);
+    Ok(cx.undefined())
+}
+
+/// Reads one `{ n, k, header, nonce, solution, personalization }` batch entry.
+/// `name` identifies the entry in error messages.
+fn batch_item_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    obj: Handle<'a, JsObject>,
+    name: &str,
+) -> NeonResult<BatchItem> {
+    let n = obj.get_value(cx, "n")?;
+    let k = obj.get_value(cx, "k")?;
+    let p = params_from_js(cx, n, k)?;
+
+    let input = obj.get_value(cx, "header")?;
+    let input = bytes_from_js(cx, input, &format!("{}.header", name))?.into_vec(cx);
+    let nonce = obj.get_value(cx, "nonce")?;
+    let nonce = bytes_from_js(cx, nonce, &format!("{}.nonce", name))?.into_vec(cx);
+    let soln = obj.get_value(cx, "solution")?;
+    let soln_name = format!("{}.solution", name);
+    let soln = bytes_from_js(cx, soln, &soln_name)?.into_vec(cx);
+    check_solution_len(cx, p, soln.len(), &soln_name)?;
+    let personalization = obj.get_value(cx, "personalization")?;
+    let personalization = personalization_from_js(cx, Some(personalization))?;
+
+    Ok(BatchItem {
+        n: p.n(),
+        k: p.k(),
+        personalization,
+        input,
+        nonce,
+        soln,
+    })
+}
+
+fn verify_batch_wrapper(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let entries = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+
+    let mut items = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.into_iter().enumerate() {
+        let name = format!("items[{}]", i);
+        let obj = match entry.downcast::<JsObject, _>(&mut cx) {
+            Ok(obj) => obj,
+            Err(_) => return cx.throw_type_error(format!("{} must be an object", name)),
+        };
+        items.push(batch_item_from_js(&mut cx, obj, &name)?);
+    }
+
+    let results = guard(&mut cx, || verify_batch(&items))?;
+
+    let out = cx.empty_array();
+    for (i, result) in results.iter().enumerate() {
+        let valid = cx.boolean(result.is_ok());
+        out.set(&mut cx, i as u32, valid)?;
+    }
+    Ok(out)
+}
+
+/// Converts a list of solution indices into a JavaScript array.
+fn indices_to_js<'a, C: Context<'a>>(cx: &mut C, indices: &[u32]) -> JsResult<'a, JsArray> {
+    let out = cx.empty_array();
+    for (i, index) in indices.iter().enumerate() {
+        let index = cx.number(*index);
+        out.set(cx, i as u32, index)?;
+    }
+    Ok(out)
+}
+
+/// `solve` runs on the JavaScript thread, so it only takes parameters no more
+/// expensive than 96,5: the solver starts from 2^(n/(k+1)+1) rows that grow to
+/// 2^k indices each, and the exponents may add up to at most this.
+const MAX_SOLVE_BITS: usize = 22;
+
+fn solve_wrapper(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let p = params_argument(&mut cx, 0)?;
+    if p.index_bit_length() + p.k() as usize > MAX_SOLVE_BITS {
+        return cx.throw_range_error(format!(
+            "solve only supports parameters up to the size of 96,5, got n={}, k={}",
+            p.n(),
+            p.k()
+        ));
+    }
+    let input = bytes_argument(&mut cx, 2, "input")?;
+    let nonce = bytes_argument(&mut cx, 3, "nonce")?;
+    let personalization = cx.argument_opt(4);
+    let p = p.with_personalization(personalization_from_js(&mut cx, personalization)?);
+
+    let solutions = catch_panic(|| solve(p, input.as_slice(&cx), nonce.as_slice(&cx)));
+    let solutions = rethrow(&mut cx, solutions)?;
+
+    // Each solution is returned as an array of indices.
+    let out = cx.empty_array();
+    for (i, indices) in solutions.iter().enumerate() {
+        let js_indices = indices_to_js(&mut cx, indices)?;
+        out.set(&mut cx, i as u32, js_indices)?;
+    }
+    Ok(out)
+}
+
+fn indices_to_minimal_wrapper(mut cx: FunctionContext) -> JsResult<JsString> {
+    let p = params_argument(&mut cx, 0)?;
+    let js_indices = cx.argument::<JsArray>(2)?.to_vec(&mut cx)?;
+    if js_indices.len() != p.solution_indices() {
+        return cx.throw_range_error(format!(
+            "indices must have {} entries for these parameters, got {}",
+            p.solution_indices(),
+            js_indices.len()
+        ));
+    }
+
+    let mut indices = Vec::with_capacity(js_indices.len());
+    for (i, index) in js_indices.into_iter().enumerate() {
+        let index = u32_from_js(&mut cx, index, &format!("indices[{}]", i))?;
+        if u64::from(index) >> p.index_bit_length() != 0 {
+            return cx.throw_range_error(format!(
+                "indices[{}] does not fit in {} bits",
+                i,
+                p.index_bit_length()
+            ));
+        }
+        indices.push(index);
+    }
+
+    let minimal = guard(&mut cx, || indices_to_minimal(p, &indices))?;
+    match minimal {
+        Ok(minimal) => Ok(cx.string(hex::encode(minimal))),
+        Err(e) => cx.throw_error(e.to_string()),
+    }
+}
+
+fn indices_from_minimal_wrapper(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let p = params_argument(&mut cx, 0)?;
+    let minimal = solution_argument(&mut cx, 2, p)?;
+
+    let indices = catch_panic(|| indices_from_minimal(p, minimal.as_slice(&cx)));
+    let indices = rethrow(&mut cx, indices)?;
+    match indices {
+        Ok(indices) => indices_to_js(&mut cx, &indices),
+        Err(e) => cx.throw_error(e.to_string()),
+    }
+}
+
+/// Converts a parsed header into a plain object, with binary fields as hex.
+fn header_to_js<'a, C: Context<'a>>(cx: &mut C, header: &BlockHeader) -> JsResult<'a, JsObject> {
+    let obj = cx.empty_object();
+    let version = cx.number(header.version);
+    obj.set(cx, "version", version)?;
+    let prev_hash = cx.string(hex::encode(header.prev_hash));
+    obj.set(cx, "prevHash", prev_hash)?;
+    let merkle_root = cx.string(hex::encode(header.merkle_root));
+    obj.set(cx, "merkleRoot", merkle_root)?;
+    let final_sapling_root = cx.string(hex::encode(header.final_sapling_root));
+    obj.set(cx, "finalSaplingRoot", final_sapling_root)?;
+    let time = cx.number(header.time);
+    obj.set(cx, "time", time)?;
+    let bits = cx.number(header.bits);
+    obj.set(cx, "bits", bits)?;
+    let nonce = cx.string(hex::encode(header.nonce));
+    obj.set(cx, "nonce", nonce)?;
+    let solution = cx.string(hex::encode(&header.solution));
+    obj.set(cx, "solution", solution)?;
+    let pastel_id = cx.string(&header.pastel_id);
+    obj.set(cx, "pastelId", pastel_id)?;
+    let signature = cx.string(hex::encode(&header.signature));
+    obj.set(cx, "signature", signature)?;
+    Ok(obj)
+}
+
+fn u32_field<'a, C: Context<'a>>(
+    cx: &mut C,
+    obj: Handle<'a, JsObject>,
+    key: &str,
+) -> NeonResult<u32> {
+    let value = obj.get_value(cx, key)?;
+    u32_from_js(cx, value, key)
+}
+
+fn bytes_field<'a, C: Context<'a>>(
+    cx: &mut C,
+    obj: Handle<'a, JsObject>,
+    key: &str,
+) -> NeonResult<Vec<u8>> {
+    let value = obj.get_value(cx, key)?;
+    Ok(bytes_from_js(cx, value, key)?.into_vec(cx))
+}
+
+fn hash_field<'a, C: Context<'a>>(
+    cx: &mut C,
+    obj: Handle<'a, JsObject>,
+    key: &str,
+) -> NeonResult<[u8; 32]> {
+    let bytes = bytes_field(cx, obj, key)?;
+    match bytes.try_into() {
+        Ok(hash) => Ok(hash),
+        Err(_) => cx.throw_range_error(format!("{} must be 32 bytes", key)),
+    }
+}
+
+/// Builds a header from an object in the shape returned by `parseHeader`.
+fn header_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    obj: Handle<'a, JsObject>,
+) -> NeonResult<BlockHeader> {
+    let version = u32_field(cx, obj, "version")?;
+    let (pastel_id, signature) = if version >= header::SIGNED_BLOCK_VERSION {
+        let pastel_id = match obj.get_value(cx, "pastelId")?.downcast::<JsString, _>(cx) {
+            Ok(pastel_id) => pastel_id.value(cx),
+            Err(_) => return cx.throw_type_error("pastelId must be a string"),
+        };
+        (pastel_id, bytes_field(cx, obj, "signature")?)
+    } else {
+        (String::new(), Vec::new())
+    };
+
+    Ok(BlockHeader {
+        version,
+        prev_hash: hash_field(cx, obj, "prevHash")?,
+        merkle_root: hash_field(cx, obj, "merkleRoot")?,
+        final_sapling_root: hash_field(cx, obj, "finalSaplingRoot")?,
+        time: u32_field(cx, obj, "time")?,
+        bits: u32_field(cx, obj, "bits")?,
+        nonce: hash_field(cx, obj, "nonce")?,
+        solution: bytes_field(cx, obj, "solution")?,
+        pastel_id,
+        signature,
+    })
+}
+
+/// Parses the header at the start of the raw block argument at position `i`.
+fn read_block_header(cx: &mut FunctionContext, i: i32) -> NeonResult<(BlockHeader, usize)> {
+    let raw = bytes_argument(cx, i, "block")?;
+    let parsed = catch_panic(|| BlockHeader::read(raw.as_slice(cx)));
+    match rethrow(cx, parsed)? {
+        Ok(parsed) => Ok(parsed),
+        Err(e) => cx.throw_error(format!("block: {}", e)),
+    }
+}
+
+fn parse_header_wrapper(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let raw = bytes_argument(&mut cx, 0, "header")?;
+    let header = catch_panic(|| BlockHeader::parse(raw.as_slice(&cx)));
+    let header = match rethrow(&mut cx, header)? {
+        Ok(header) => header,
+        Err(e) => return cx.throw_error(format!("header: {}", e)),
+    };
+    header_to_js(&mut cx, &header)
+}
+
+fn parse_block_header_wrapper(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let (header, size) = read_block_header(&mut cx, 0)?;
+    let obj = header_to_js(&mut cx, &header)?;
+    // Lets callers find where the transactions start.
+    let size = cx.number(size as f64);
+    obj.set(&mut cx, "size", size)?;
+    Ok(obj)
+}
+
+fn encode_header_wrapper(mut cx: FunctionContext) -> JsResult<JsString> {
+    let obj = cx.argument::<JsObject>(0)?;
+    let header = header_from_js(&mut cx, obj)?;
+    Ok(cx.string(hex::encode(header.serialize())))
+}
+
+fn get_data_for_equihash_validation_wrapper(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let (header, _) = read_block_header(&mut cx, 0)?;
+
+    let obj = cx.empty_object();
+    let input = cx.string(hex::encode(header.equihash_input()));
+    obj.set(&mut cx, "equihash_input_hex_string", input)?;
+    let solution = cx.string(hex::encode(&header.solution));
+    obj.set(&mut cx, "solution_hex_string", solution)?;
+    Ok(obj)
+}
+
+fn verify_raw_header_wrapper(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let raw = bytes_argument(&mut cx, 0, "header")?;
+    let p = params_argument(&mut cx, 1)?;
+
+    let header = catch_panic(|| BlockHeader::parse(raw.as_slice(&cx)));
+    let header = match rethrow(&mut cx, header)? {
+        Ok(header) => header,
+        Err(e) => return cx.throw_error(format!("header: {}", e)),
+    };
+
+    let result = guard(&mut cx, || header.verify_solution(p.n(), p.k()))?;
+    let obj = verification_to_js(&mut cx, &result)?;
+    let header = header_to_js(&mut cx, &header)?;
+    obj.set(&mut cx, "header", header)?;
+    Ok(obj)
+}
+
+fn check_proof_of_work_wrapper(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let raw = bytes_argument(&mut cx, 0, "header")?;
+    let pow_limit_bits = match cx.argument_opt(1) {
+        Some(v) if !v.is_a::<JsUndefined, _>(&mut cx) => u32_from_js(&mut cx, v, "powLimitBits")?,
+        _ => pow::POW_LIMIT_BITS,
+    };
+
+    let header = catch_panic(|| BlockHeader::parse(raw.as_slice(&cx)));
+    let header = match rethrow(&mut cx, header)? {
+        Ok(header) => header,
+        Err(e) => return cx.throw_error(format!("header: {}", e)),
+    };
+    let (hash, check, difficulty, target) = guard(&mut cx, || {
+        let hash = header.hash();
+        let check = pow::check_proof_of_work(&hash, header.bits, pow_limit_bits);
+        let difficulty = pow::difficulty(header.bits, pow_limit_bits);
+        let target = pow::target_from_compact(header.bits)
+            .ok()
+            .map(|target| (target, pow::work(target)));
+        (hash, check, difficulty, target)
+    })?;
+
+    let obj = match check {
+        Ok(()) => {
+            let obj = cx.empty_object();
+            let valid = cx.boolean(true);
+            obj.set(&mut cx, "valid", valid)?;
+            obj
+        }
+        Err(e) => failure_object(&mut cx, e.code(), &e.to_string())?,
+    };
+
+    // Hashes are shown byte-reversed, as the node displays them, so that they
+    // compare numerically with the target.
+    let mut display_hash = hash;
+    display_hash.reverse();
+    let display_hash = cx.string(hex::encode(display_hash));
+    obj.set(&mut cx, "hash", display_hash)?;
+    let difficulty = cx.number(difficulty);
+    obj.set(&mut cx, "difficulty", difficulty)?;
+    if let Some((target, work)) = target {
+        let target_hex = cx.string(target.to_string());
+        obj.set(&mut cx, "target", target_hex)?;
+        let work = cx.string(work.to_string());
+        obj.set(&mut cx, "work", work)?;
+    }
+    Ok(obj)
+}
+
+// Register the module and export the wrapper functions to JavaScript.
+#[neon::main]
+fn main(mut m: ModuleContext) -> NeonResult<()> {
+    m.export_function("is_validSolution", is_valid_solution_wrapper)?;
+    m.export_function("is_valid_solution", is_valid_solution_with_nonce_wrapper)?;
+    m.export_function("verifySolutionDetailed", verify_solution_detailed_wrapper)?;
+    m.export_function("verifySolutionAsync", verify_solution_async_wrapper)?;
+    m.export_function("headerContextNew", header_context_new_wrapper)?;
+    m.export_function("headerContextVerify", header_context_verify_wrapper)?;
+    m.export_function(
+        "headerContextVerifyDetailed",
+        header_context_verify_detailed_wrapper,
+    )?;
+    m.export_function("setMaxInFlight", set_max_in_flight_wrapper)?;
+    m.export_function("verifyBatch", verify_batch_wrapper)?;
+    m.export_function("solve", solve_wrapper)?;
+    m.export_function("indicesToMinimal", indices_to_minimal_wrapper)?;
+    m.export_function("indicesFromMinimal", indices_from_minimal_wrapper)?;
+    m.export_function("parseHeader", parse_header_wrapper)?;
+    m.export_function("parseBlockHeader", parse_block_header_wrapper)?;
+    m.export_function("encodeHeader", encode_header_wrapper)?;
+    m.export_function("verifyRawHeader", verify_raw_header_wrapper)?;
+    m.export_function("checkProofOfWork", check_proof_of_work_wrapper)?;
+    m.export_function(
+        "getDataForEquihashValidation",
+        get_data_for_equihash_validation_wrapper,
+    )?;
+    Ok(())
+}
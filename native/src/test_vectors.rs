@@ -1,9 +0,0 @@
-#[cfg(test)]
-mod invalid;
-mod valid;
-
-#[cfg(test)]
-pub(crate) use invalid::INVALID_TEST_VECTORS;
-
-#[cfg(test)]
-pub(crate) use valid::VALID_TEST_VECTORS;
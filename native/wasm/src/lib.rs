@@ -0,0 +1,333 @@
+//! WebAssembly exports mirroring the Node binding, for browsers and edge
+//! runtimes. Binary arguments and fields are hex strings, as in the Node API.
+
+use equihash::header::BlockHeader;
+use equihash::verify::{self, Params, PERSONALIZATION_PRESETS, ZCASH_PERSONALIZATION};
+use std::convert::TryInto;
+use wasm_bindgen::prelude::*;
+
+/// An argument was rejected. Thrown to JavaScript as an `Error` carrying the
+/// same message the Node binding uses.
+#[derive(Debug, PartialEq)]
+pub struct Error(String);
+
+impl From<Error> for JsValue {
+    fn from(e: Error) -> JsValue {
+        JsError::new(&e.0).into()
+    }
+}
+
+fn hex_argument(name: &str, value: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(value).map_err(|e| match e {
+        hex::FromHexError::OddLength => Error(format!(
+            "{} must have an even number of hex digits, got {}",
+            name,
+            value.len()
+        )),
+        hex::FromHexError::InvalidHexCharacter { c, index } => Error(format!(
+            "{} has non-hex character {:?} at position {}",
+            name, c, index
+        )),
+        e => Error(format!("{}: {}", name, e)),
+    })
+}
+
+fn params_argument(n: u32, k: u32) -> Result<Params, Error> {
+    Params::new(n, k)
+        .map_err(|_| Error(format!("unsupported Equihash parameters n={}, k={}", n, k)))
+}
+
+/// Decodes the minimal solution and checks its length against `p`.
+fn solution_argument(p: Params, value: &str) -> Result<Vec<u8>, Error> {
+    let soln = hex_argument("solution", value)?;
+    if soln.len() != p.solution_len() {
+        return Err(Error(format!(
+            "solution must be {} bytes for these parameters, got {}",
+            p.solution_len(),
+            soln.len()
+        )));
+    }
+    Ok(soln)
+}
+
+/// Either a name from `PERSONALIZATION_PRESETS` or the 8-character prefix
+/// itself, defaulting to Zcash's.
+fn personalization_argument(value: Option<String>) -> Result<[u8; 8], Error> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(ZCASH_PERSONALIZATION),
+    };
+    if let Some((_, preset)) = PERSONALIZATION_PRESETS
+        .iter()
+        .find(|(name, _)| *name == value)
+    {
+        return Ok(*preset);
+    }
+    value.as_bytes().try_into().map_err(|_| {
+        Error(format!(
+            "personalization: expected a preset name or 8 bytes, got {:?}",
+            value
+        ))
+    })
+}
+
+/// The outcome of `verifySolutionDetailed`. Only `valid` is set for a valid
+/// solution; the location fields are set when merging the tree failed.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Verification {
+    #[wasm_bindgen(readonly)]
+    pub valid: bool,
+    #[wasm_bindgen(readonly)]
+    pub code: Option<String>,
+    #[wasm_bindgen(readonly)]
+    pub message: Option<String>,
+    #[wasm_bindgen(readonly)]
+    pub level: Option<u32>,
+    #[wasm_bindgen(readonly)]
+    pub left: Option<u32>,
+    #[wasm_bindgen(readonly)]
+    pub right: Option<u32>,
+}
+
+impl From<Result<(), verify::Error>> for Verification {
+    fn from(result: Result<(), verify::Error>) -> Self {
+        let e = match result {
+            Ok(()) => {
+                return Verification {
+                    valid: true,
+                    code: None,
+                    message: None,
+                    level: None,
+                    left: None,
+                    right: None,
+                }
+            }
+            Err(e) => e,
+        };
+        let location = e.location();
+        Verification {
+            valid: false,
+            code: Some(e.kind().code().to_string()),
+            message: Some(e.to_string()),
+            level: location.map(|l| l.level),
+            left: location.map(|l| l.left as u32),
+            right: location.map(|l| l.right as u32),
+        }
+    }
+}
+
+/// A parsed header, with the same fields as the Node binding's `parseHeader`.
+/// `size` is only set by `parseBlockHeader`.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Header {
+    #[wasm_bindgen(readonly)]
+    pub version: u32,
+    #[wasm_bindgen(readonly, js_name = prevHash)]
+    pub prev_hash: String,
+    #[wasm_bindgen(readonly, js_name = merkleRoot)]
+    pub merkle_root: String,
+    #[wasm_bindgen(readonly, js_name = finalSaplingRoot)]
+    pub final_sapling_root: String,
+    #[wasm_bindgen(readonly)]
+    pub time: u32,
+    #[wasm_bindgen(readonly)]
+    pub bits: u32,
+    #[wasm_bindgen(readonly)]
+    pub nonce: String,
+    #[wasm_bindgen(readonly)]
+    pub solution: String,
+    #[wasm_bindgen(readonly, js_name = pastelId)]
+    pub pastel_id: String,
+    #[wasm_bindgen(readonly)]
+    pub signature: String,
+    #[wasm_bindgen(readonly)]
+    pub size: Option<u32>,
+}
+
+impl From<BlockHeader> for Header {
+    fn from(header: BlockHeader) -> Self {
+        Header {
+            version: header.version,
+            prev_hash: hex::encode(header.prev_hash),
+            merkle_root: hex::encode(header.merkle_root),
+            final_sapling_root: hex::encode(header.final_sapling_root),
+            time: header.time,
+            bits: header.bits,
+            nonce: hex::encode(header.nonce),
+            solution: hex::encode(&header.solution),
+            pastel_id: header.pastel_id,
+            signature: hex::encode(&header.signature),
+            size: None,
+        }
+    }
+}
+
+/// Checks a solution for a header that already ends with its nonce.
+#[wasm_bindgen(js_name = is_validSolution)]
+pub fn is_valid_solution(
+    n: u32,
+    k: u32,
+    block_header: &str,
+    solution: &str,
+) -> Result<bool, Error> {
+    let p = params_argument(n, k)?;
+    let input = hex_argument("blockHeader", block_header)?;
+    let soln = solution_argument(p, solution)?;
+    Ok(verify::is_valid_solution_with_params(p, &input, &[], &soln).is_ok())
+}
+
+/// Like `is_validSolution`, but reports why a solution was rejected.
+#[wasm_bindgen(js_name = verifySolutionDetailed)]
+pub fn verify_solution_detailed(
+    n: u32,
+    k: u32,
+    block_header: &str,
+    solution: &str,
+    personalization: Option<String>,
+) -> Result<Verification, Error> {
+    let p = params_argument(n, k)?.with_personalization(personalization_argument(personalization)?);
+    let input = hex_argument("blockHeader", block_header)?;
+    let soln = solution_argument(p, solution)?;
+    Ok(verify::is_valid_solution_with_params(p, &input, &[], &soln).into())
+}
+
+/// Decodes a minimal solution into its indices.
+#[wasm_bindgen(js_name = indicesFromMinimal)]
+pub fn indices_from_minimal(n: u32, k: u32, solution: &str) -> Result<Vec<u32>, Error> {
+    let p = params_argument(n, k)?;
+    let minimal = solution_argument(p, solution)?;
+    verify::indices_from_minimal(p, &minimal).map_err(|e| Error(e.to_string()))
+}
+
+/// Parses a serialized Pastel header. Fails if there is data left over.
+#[wasm_bindgen(js_name = parseHeader)]
+pub fn parse_header(header: &str) -> Result<Header, Error> {
+    let raw = hex_argument("header", header)?;
+    BlockHeader::parse(&raw)
+        .map(Header::from)
+        .map_err(|e| Error(format!("header: {}", e)))
+}
+
+/// Parses the header at the start of a raw block. `size` is the header's
+/// length in bytes, which is where the transactions start.
+#[wasm_bindgen(js_name = parseBlockHeader)]
+pub fn parse_block_header(block: &str) -> Result<Header, Error> {
+    let raw = hex_argument("block", block)?;
+    let (header, size) = BlockHeader::read(&raw).map_err(|e| Error(format!("header: {}", e)))?;
+    Ok(Header {
+        size: Some(size as u32),
+        ..header.into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        indices_from_minimal, is_valid_solution, parse_block_header, parse_header,
+        verify_solution_detailed,
+    };
+    use equihash::header::BlockHeader;
+    use equihash::test_vectors::{INVALID_TEST_VECTORS, PASTEL_V5_BLOCK, VALID_TEST_VECTORS};
+    use equihash::verify::indices_to_minimal;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// The input followed by the nonce, as `is_validSolution` takes it.
+    fn header_hex(input: &[u8], nonce: &[u8]) -> String {
+        hex::encode([input, nonce].concat())
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn valid_test_vectors() {
+        for tv in VALID_TEST_VECTORS {
+            let (n, k) = (tv.params.n(), tv.params.k());
+            let header = header_hex(tv.input, &tv.nonce);
+            for soln in tv.solutions {
+                let minimal = hex::encode(indices_to_minimal(tv.params, soln).unwrap());
+                assert!(is_valid_solution(n, k, &header, &minimal).unwrap());
+                assert!(
+                    verify_solution_detailed(n, k, &header, &minimal, None)
+                        .unwrap()
+                        .valid
+                );
+                assert_eq!(indices_from_minimal(n, k, &minimal).unwrap(), soln.to_vec());
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn invalid_test_vectors() {
+        for tv in INVALID_TEST_VECTORS {
+            let (n, k) = (tv.params.n(), tv.params.k());
+            let header = header_hex(tv.input, &tv.nonce);
+            let minimal = hex::encode(indices_to_minimal(tv.params, tv.solution).unwrap());
+            assert!(!is_valid_solution(n, k, &header, &minimal).unwrap());
+
+            let result = verify_solution_detailed(n, k, &header, &minimal, None).unwrap();
+            assert!(!result.valid);
+            assert_eq!(result.code.as_deref(), Some(tv.error.code()));
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn personalization() {
+        let tv = &VALID_TEST_VECTORS[0];
+        let (n, k) = (tv.params.n(), tv.params.k());
+        let header = header_hex(tv.input, &tv.nonce);
+        let minimal = hex::encode(indices_to_minimal(tv.params, tv.solutions[0]).unwrap());
+
+        let zcash = Some("zcash".to_string());
+        assert!(
+            verify_solution_detailed(n, k, &header, &minimal, zcash)
+                .unwrap()
+                .valid
+        );
+        let bgold = Some("bgold".to_string());
+        assert!(
+            !verify_solution_detailed(n, k, &header, &minimal, bgold)
+                .unwrap()
+                .valid
+        );
+        let bad = Some("short".to_string());
+        assert!(verify_solution_detailed(n, k, &header, &minimal, bad).is_err());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn pastel_header() {
+        let block = hex::decode(PASTEL_V5_BLOCK).unwrap();
+        let (expected, size) = BlockHeader::read(&block).unwrap();
+
+        let header = parse_header(&PASTEL_V5_BLOCK[..2 * size]).unwrap();
+        assert_eq!(header.size, None);
+        assert_eq!(header.version, expected.version);
+        assert_eq!(header.prev_hash, hex::encode(expected.prev_hash));
+        assert_eq!(header.pastel_id, expected.pastel_id);
+        assert_eq!(header.solution, hex::encode(&expected.solution));
+
+        assert!(parse_header(PASTEL_V5_BLOCK).is_err());
+        let header = parse_block_header(PASTEL_V5_BLOCK).unwrap();
+        assert_eq!(header.size, Some(size as u32));
+        assert_eq!(header.signature, hex::encode(&expected.signature));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn argument_errors() {
+        let header = "00".repeat(140);
+        let solution = "00".repeat(1344);
+        assert!(is_valid_solution(200, 9, &header, &solution).is_ok());
+
+        let e = is_valid_solution(200, 9, "0", &solution).unwrap_err();
+        assert_eq!(
+            e.0,
+            "blockHeader must have an even number of hex digits, got 1"
+        );
+        let e = is_valid_solution(200, 9, "zz", &solution).unwrap_err();
+        assert_eq!(e.0, "blockHeader has non-hex character 'z' at position 0");
+        let e = is_valid_solution(200, 8, &header, &solution).unwrap_err();
+        assert_eq!(e.0, "unsupported Equihash parameters n=200, k=8");
+        let e = is_valid_solution(200, 9, &header, "00").unwrap_err();
+        assert_eq!(
+            e.0,
+            "solution must be 1344 bytes for these parameters, got 1"
+        );
+    }
+}